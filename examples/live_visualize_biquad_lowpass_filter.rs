@@ -22,8 +22,10 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 use audio_visualizer::dynamic::live_input::{list_input_devs, AudioDevAndCfg};
+use audio_visualizer::dynamic::window_top_btm::visualize_minifb::WindowConfig;
 use audio_visualizer::dynamic::window_top_btm::{open_window_connect_audio, TransformFn};
-use biquad::{Biquad, Coefficients, DirectForm1, ToHertz, Type, Q_BUTTERWORTH_F32};
+use audio_visualizer::filters::{BiquadLowpass, Q_BUTTERWORTH};
+use plotters::style::{CYAN, RED};
 use std::io::{stdin, BufRead};
 
 /// Example that creates a live visualization of realtime audio data
@@ -39,21 +41,23 @@ fn main() {
         "time (seconds)",
         "Amplitude (with Biquad Lowpass filter)",
         AudioDevAndCfg::new(Some(in_dev), None),
-        // lowpass filter
-        TransformFn::Basic(|vals, sampling_rate| {
-            // Cutoff and sampling frequencies
-            let f0 = 80.hz();
-            let fs = sampling_rate.hz();
-
-            // Create coefficients for the biquads
-            let coeffs =
-                Coefficients::<f32>::from_params(Type::LowPass, fs, f0, Q_BUTTERWORTH_F32).unwrap();
-            let mut lowpassed_data = Vec::with_capacity(vals.len());
-            let mut biquad_lpf = DirectForm1::<f32>::new(coeffs);
-            vals.iter()
-                .for_each(|val| lowpassed_data.push(biquad_lpf.run(*val)));
-            lowpassed_data
-        }),
+        // original audio data and lowpass filter with a cutoff frequency of 80 Hz; the
+        // whole audio history is filtered each frame, so the filter starts with a fresh state
+        TransformFn::Overlay(vec![
+            (
+                TransformFn::Basic(|vals, _| vals.to_vec()),
+                CYAN,
+                "original",
+            ),
+            (
+                TransformFn::Basic(|vals, sampling_rate| {
+                    BiquadLowpass::new(80.0, Q_BUTTERWORTH, sampling_rate).process(vals)
+                }),
+                RED,
+                "lowpass (80 Hz)",
+            ),
+        ]),
+        WindowConfig::default(),
     );
 }
 
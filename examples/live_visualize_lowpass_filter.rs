@@ -22,6 +22,7 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 use audio_visualizer::dynamic::live_input::{list_input_devs, AudioDevAndCfg};
+use audio_visualizer::dynamic::window_top_btm::visualize_minifb::WindowConfig;
 use audio_visualizer::dynamic::window_top_btm::{open_window_connect_audio, TransformFn};
 use cpal::traits::DeviceTrait;
 use lowpass_filter::lowpass_filter;
@@ -46,6 +47,7 @@ fn main() {
             lowpass_filter(&mut data_f32, sampling_rate, 80.0);
             data_f32
         }),
+        WindowConfig::default(),
     );
 }
 
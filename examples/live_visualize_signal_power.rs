@@ -22,7 +22,9 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 use audio_visualizer::dynamic::live_input::{list_input_devs, AudioDevAndCfg};
+use audio_visualizer::dynamic::window_top_btm::visualize_minifb::WindowConfig;
 use audio_visualizer::dynamic::window_top_btm::{open_window_connect_audio, TransformFn};
+use audio_visualizer::util::dsp::latest_n;
 use cpal::traits::DeviceTrait;
 use ringbuffer::{AllocRingBuffer, RingBuffer};
 use std::cell::{Cell, RefCell};
@@ -43,9 +45,8 @@ fn main() {
 
         // equals 11.6ms with 44.1kHz sampling rate or 10.7ms with 48kHz sampling rate.
         const NUM_SAMPLES: usize = 256;
-        let skip_elements = audio.len() - NUM_SAMPLES;
-        // spectrum analysis only of the latest 46ms
-        let relevant_samples = &audio[skip_elements..];
+        // only analyze the latest samples
+        let relevant_samples = latest_n(audio, NUM_SAMPLES);
 
         let power_sum = relevant_samples
             .iter()
@@ -89,6 +90,7 @@ fn main() {
         "y-axis",
         AudioDevAndCfg::new(Some(in_dev), None),
         TransformFn::Complex(&to_power_fn),
+        WindowConfig::default(),
     );
 }
 
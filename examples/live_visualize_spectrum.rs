@@ -22,28 +22,29 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 use audio_visualizer::dynamic::live_input::{list_input_devs, AudioDevAndCfg};
+use audio_visualizer::dynamic::smoothing::PeakDecaySmoother;
+use audio_visualizer::dynamic::window_top_btm::visualize_minifb::WindowConfig;
 use audio_visualizer::dynamic::window_top_btm::{open_window_connect_audio, TransformFn};
+use audio_visualizer::util::dsp::latest_n;
 use cpal::traits::DeviceTrait;
 use spectrum_analyzer::scaling::divide_by_N;
 use spectrum_analyzer::windows::hann_window;
-use spectrum_analyzer::{samples_fft_to_spectrum, FrequencyLimit, FrequencyValue};
+use spectrum_analyzer::{samples_fft_to_spectrum, FrequencyLimit};
 use std::cell::RefCell;
-use std::cmp::max;
 use std::io::{stdin, BufRead};
 
 /// Example that creates a live visualization of the frequency spectrum of realtime audio data
 /// **Execute this with `--release`, otherwise it is very laggy!**.
 fn main() {
-    // Contains the data for the spectrum to be visualized. It contains ordered pairs of
-    // `(frequency, frequency_value)`. During each iteration, the frequency value gets
-    // combined with `max(old_value * smoothing_factor, new_value)`.
-    let visualize_spectrum: RefCell<Vec<(f64, f64)>> = RefCell::new(vec![(0.0, 0.0); 1024]);
+    // Smoothens the spectrum to be visualized. During each iteration, the frequency value
+    // gets combined with `max(old_value * decay, new_value)`. The decay depends on the time
+    // between two frames, so that the smoothing is independent of the frame rate.
+    let smoother = RefCell::new(PeakDecaySmoother::new(40.0));
 
-    // Closure that captures `visualize_spectrum`.
+    // Closure that captures `smoother`.
     let to_spectrum_fn = move |audio: &[f32], sampling_rate| {
-        let skip_elements = audio.len() - 2048;
         // spectrum analysis only of the latest 46ms
-        let relevant_samples = &audio[skip_elements..skip_elements + 2048];
+        let relevant_samples = latest_n(audio, 2048);
 
         // do FFT
         let hann_window = hann_window(relevant_samples);
@@ -57,22 +58,18 @@ fn main() {
 
         // now smoothen the spectrum; old values are decreased a bit and replaced,
         // if the new value is higher
+        let latest_values = latest_spectrum
+            .data()
+            .iter()
+            .map(|(_, fr_val)| fr_val.val() as f64 * 5000.0)
+            .collect::<Vec<_>>();
+        let mut smoother = smoother.borrow_mut();
         latest_spectrum
             .data()
             .iter()
-            .zip(visualize_spectrum.borrow_mut().iter_mut())
-            .for_each(|((fr_new, fr_val_new), (fr_old, fr_val_old))| {
-                // actually only required in very first iteration
-                *fr_old = fr_new.val() as f64;
-                let old_val = *fr_val_old * 0.84;
-                let max = max(
-                    *fr_val_new * 5000.0_f32.into(),
-                    FrequencyValue::from(old_val as f32),
-                );
-                *fr_val_old = max.val() as f64;
-            });
-
-        visualize_spectrum.borrow().clone()
+            .zip(smoother.smooth(&latest_values))
+            .map(|((fr, _), fr_val)| (fr.val() as f64, *fr_val))
+            .collect::<Vec<_>>()
     };
 
     let in_dev = select_input_dev();
@@ -87,6 +84,7 @@ fn main() {
         "y-axis",
         AudioDevAndCfg::new(Some(in_dev), None),
         TransformFn::Complex(&to_spectrum_fn),
+        WindowConfig::default(),
     );
 }
 
@@ -0,0 +1,139 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Ambient visual mode: the whole window pulses with the envelope of the audio input
+//! instead of showing charts. See [`open_ambient_visualizer`].
+
+use crate::dynamic::live_input::{setup_audio_input_loop, AudioDevAndCfg};
+use crate::dynamic::smoothing::LevelBallistics;
+use crate::dynamic::window_top_btm::pixel_buf::PixelBuf;
+use crate::dynamic::window_top_btm::visualize_minifb::{DEFAULT_H, DEFAULT_W};
+use crate::util::dsp::latest_n;
+use cpal::traits::StreamTrait;
+use minifb::{Key, Window, WindowOptions};
+use ringbuffer::{AllocRingBuffer, RingBuffer};
+use std::borrow::Borrow;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Refresh rate of the window; the envelope changes slowly compared to a waveform.
+const REFRESH_RATE: f64 = 60.0;
+/// Duration of the latest audio data of which the RMS is calculated.
+const RMS_WINDOW_S: f64 = 0.05;
+/// Attack time of the envelope; the background brightens quickly on onsets.
+const ATTACK_MS: f64 = 10.0;
+/// Release time of the envelope; the background fades out slowly.
+const RELEASE_MS: f64 = 300.0;
+/// Dynamic range of the brightness: an RMS at full scale (0 dB) is the brightest color,
+/// an RMS of `-DB_RANGE` dB and below is black.
+const DB_RANGE: f64 = 60.0;
+/// Color of the background at full brightness.
+const COLOR: (u8, u8, u8) = (0, 255, 255);
+
+/// Opens a GUI window whose background brightness pulses with the envelope of the audio
+/// input (or the default input device), e.g. as reactive background. It draws no charts.
+///
+/// Each frame, the RMS of the latest 50 ms of audio is smoothed with a short attack and a
+/// long release (see [`LevelBallistics`]) and mapped logarithmically to the brightness.
+///
+/// This operation is blocking. It returns, when the GUI window is closed or `Escape` is
+/// pressed.
+///
+/// # Parameters
+/// - `name` Name of the GUI window
+/// - `input_dev_and_cfg` See [`AudioDevAndCfg`].
+pub fn open_ambient_visualizer(name: &str, input_dev_and_cfg: AudioDevAndCfg) {
+    let sample_rate = input_dev_and_cfg.cfg().sample_rate.0 as f64;
+    let rms_len = (RMS_WINDOW_S * sample_rate).ceil() as usize;
+    let mut buf = AllocRingBuffer::new(rms_len.next_power_of_two());
+    buf.fill(0.0);
+    let latest_audio_data = Arc::new(Mutex::new(buf));
+    let (stream, _cfg) = setup_audio_input_loop(latest_audio_data.clone(), input_dev_and_cfg, None);
+
+    let mut window = Window::new(name, DEFAULT_W, DEFAULT_H, WindowOptions::default()).unwrap();
+    window.limit_update_rate(Some(Duration::from_secs_f64(1.0 / REFRESH_RATE)));
+    let mut pixel_buf = PixelBuf(vec![0_u32; DEFAULT_W * DEFAULT_H]);
+    let mut envelope = LevelBallistics::new(ATTACK_MS, RELEASE_MS);
+
+    stream.play().unwrap();
+    while window.is_open() && !window.is_key_down(Key::Escape) {
+        // lock released immediately after oneliner
+        let latest_audio_data = latest_audio_data.lock().unwrap().to_vec();
+        let level = envelope.update(rms(latest_n(&latest_audio_data, rms_len)));
+        pixel_buf.0.fill(background_color(brightness(level)));
+        window
+            .update_with_buffer(pixel_buf.borrow(), DEFAULT_W, DEFAULT_H)
+            .unwrap();
+    }
+    stream.pause().unwrap();
+}
+
+/// Root mean square of the samples. Zero for no samples.
+fn rms(samples: &[f32]) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let sum = samples.iter().map(|x| (*x as f64).powi(2)).sum::<f64>();
+    (sum / samples.len() as f64).sqrt()
+}
+
+/// Maps an RMS level to a brightness in `[0.0; 1.0]` with a logarithmic scale, so that
+/// quiet passages are still visible. See [`DB_RANGE`].
+fn brightness(level: f64) -> f64 {
+    let db = 20.0 * level.log10();
+    ((db + DB_RANGE) / DB_RANGE).clamp(0.0, 1.0)
+}
+
+/// [`COLOR`] with the given brightness in the pixel format of [`minifb`] (`0RGB`).
+fn background_color(brightness: f64) -> u32 {
+    let (r, g, b) = COLOR;
+    let scale = |c: u8| (c as f64 * brightness).round() as u32;
+    (scale(r) << 16) | (scale(g) << 8) | scale(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rms() {
+        assert_eq!(rms(&[]), 0.0);
+        assert_eq!(rms(&[0.5, -0.5]), 0.5);
+    }
+
+    #[test]
+    fn test_brightness() {
+        assert_eq!(brightness(0.0), 0.0);
+        assert_eq!(brightness(1.0), 1.0);
+        assert_eq!(brightness(2.0), 1.0);
+        // -30 dB is in the middle of the dynamic range
+        assert!((brightness(10_f64.powf(-1.5)) - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_background_color() {
+        assert_eq!(background_color(0.0), 0x00_00_00);
+        assert_eq!(background_color(1.0), 0x00_ff_ff);
+        assert_eq!(background_color(0.5), 0x00_80_80);
+    }
+}
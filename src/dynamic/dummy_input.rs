@@ -0,0 +1,143 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+//! Synthetic audio input without audio hardware. See [`DummyInput`].
+
+use ringbuffer::AllocRingBuffer;
+use std::fmt::{Debug, Formatter};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Interval in which the thread of [`DummyInput`] appends new samples.
+const TICK: Duration = Duration::from_millis(10);
+
+/// Function that returns the amplitude of the synthetic signal at the given time in
+/// seconds. See [`DummyInput::new`].
+pub type SignalFn = Box<dyn Fn(f64) -> f32 + Send>;
+
+/// Replacement of [`super::live_input::setup_audio_input_loop`] without audio hardware,
+/// e.g. for tests in CI.
+///
+/// A thread appends the samples of a synthetic signal to the ringbuffer in real time, like
+/// the audio input stream does. The samples themselves are deterministic; only the point in
+/// time at which they arrive depends on the scheduler. The thread stops, when the
+/// [`DummyInput`] is dropped.
+pub struct DummyInput {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl DummyInput {
+    /// Starts appending the samples of `signal` to `latest_audio_data` with the given
+    /// sampling rate.
+    pub fn new(
+        latest_audio_data: Arc<Mutex<AllocRingBuffer<f32>>>,
+        sample_rate: u32,
+        signal: SignalFn,
+    ) -> Self {
+        assert!(sample_rate > 0, "sample_rate must be positive!");
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let stop = stop.clone();
+            std::thread::spawn(move || {
+                let begin = Instant::now();
+                let mut generated = 0_u64;
+                while !stop.load(Ordering::Relaxed) {
+                    // as many samples as the real time since the start requires
+                    let due = (begin.elapsed().as_secs_f64() * sample_rate as f64) as u64;
+                    latest_audio_data
+                        .lock()
+                        .unwrap()
+                        .extend((generated..due).map(|i| signal(i as f64 / sample_rate as f64)));
+                    generated = due;
+                    std::thread::sleep(TICK);
+                }
+            })
+        };
+        Self {
+            stop,
+            thread: Some(thread),
+        }
+    }
+
+    /// Like [`Self::new`] with a sine wave of the given frequency and amplitude.
+    pub fn sine(
+        latest_audio_data: Arc<Mutex<AllocRingBuffer<f32>>>,
+        sample_rate: u32,
+        frequency: f32,
+        amplitude: f32,
+    ) -> Self {
+        Self::new(
+            latest_audio_data,
+            sample_rate,
+            Box::new(move |t| {
+                amplitude * (2.0 * std::f64::consts::PI * frequency as f64 * t).sin() as f32
+            }),
+        )
+    }
+}
+
+impl Debug for DummyInput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DummyInput")
+            .field("stopped", &self.stop.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+impl Drop for DummyInput {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            thread.join().unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ringbuffer::RingBuffer;
+
+    #[test]
+    fn test_dummy_input_fills_ringbuffer() {
+        let buf = Arc::new(Mutex::new(AllocRingBuffer::new(65536)));
+        let input = DummyInput::new(buf.clone(), 8000, Box::new(|t| t as f32));
+        let begin = Instant::now();
+        while buf.lock().unwrap().len() < 100 {
+            assert!(begin.elapsed() < Duration::from_secs(10), "no audio data");
+            std::thread::sleep(TICK);
+        }
+        drop(input);
+
+        // the samples follow the signal without gaps
+        let samples = buf.lock().unwrap().to_vec();
+        assert_eq!(samples[0], 0.0);
+        samples
+            .windows(2)
+            .for_each(|pair| assert!((pair[1] - pair[0] - 1.0 / 8000.0).abs() < 1e-6));
+    }
+}
@@ -24,14 +24,20 @@ SOFTWARE.
 //! This module enables to record audio and store the latest audio data in a synchronized
 //! ringbuffer. See [`setup_audio_input_loop`].
 //!
-//! It uses the [`cpal`] crate to record audio.
+//! It uses the [`cpal`] crate to record audio. Messages are logged with the [`log`] crate.
 
+use crate::ChannelGain;
 use cpal::traits::{DeviceTrait, HostTrait};
 use cpal::Device;
 use ringbuffer::AllocRingBuffer;
-use std::fmt::{Debug, Formatter};
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
 use std::sync::{Arc, Mutex};
 
+/// Callback that gets invoked from the error callback of the [`cpal`] input stream,
+/// for example if the audio device gets disconnected. See [`setup_audio_input_loop`].
+pub type StreamErrorCallback = Arc<dyn Fn(cpal::StreamError) + Send + Sync>;
+
 /// Describes the audio input device that should be used and the config for the input stream.
 /// Caller must be certain, that the config works for the given device on the current platform.
 pub struct AudioDevAndCfg {
@@ -41,24 +47,82 @@ pub struct AudioDevAndCfg {
     cfg: cpal::StreamConfig,
 }
 
+/// Error of [`AudioDevAndCfg::try_new`], if no usable audio input device is available.
+#[derive(Debug)]
+pub enum InputDeviceError {
+    /// No device is passed and the system has no (usable) default audio input device, e.g.
+    /// on a server without capture hardware.
+    NoDevice {
+        /// Name of the [`cpal`] host that was queried for the default input device.
+        host: String,
+    },
+    /// No config is passed and the device has no default input config.
+    NoDefaultConfig(cpal::DefaultStreamConfigError),
+}
+
+impl Display for InputDeviceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoDevice { host } => {
+                write!(f, "No default audio input device found for host {}", host)
+            }
+            Self::NoDefaultConfig(e) => {
+                write!(f, "The audio input device has no default config: {}", e)
+            }
+        }
+    }
+}
+
+impl Error for InputDeviceError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::NoDevice { .. } => None,
+            Self::NoDefaultConfig(e) => Some(e),
+        }
+    }
+}
+
+impl From<cpal::DefaultStreamConfigError> for InputDeviceError {
+    fn from(e: cpal::DefaultStreamConfigError) -> Self {
+        Self::NoDefaultConfig(e)
+    }
+}
+
 impl AudioDevAndCfg {
     /// Creates an instance. If no device is passed, it falls back to the default input
-    /// device of the system.
+    /// device of the system. Panics, if it is not present. See [`Self::try_new`].
     pub fn new(
         preferred_dev: Option<cpal::Device>,
         preferred_cfg: Option<cpal::StreamConfig>,
     ) -> Self {
-        let dev = preferred_dev.unwrap_or_else(|| {
-            let host = cpal::default_host();
-            host.default_input_device().unwrap_or_else(|| {
-                panic!(
-                    "No default audio input device found for host {}",
-                    host.id().name()
-                )
-            })
-        });
-        let cfg = preferred_cfg.unwrap_or_else(|| dev.default_input_config().unwrap().config());
-        Self { dev, cfg }
+        Self::try_new(preferred_dev, preferred_cfg).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Like [`Self::new`] but returns an [`InputDeviceError`] instead of panicking, if no
+    /// device is passed and the system has no default input device, or if no config is
+    /// passed and the device has no default config. This way, the caller can degrade
+    /// gracefully, e.g. to file-only visualization.
+    pub fn try_new(
+        preferred_dev: Option<cpal::Device>,
+        preferred_cfg: Option<cpal::StreamConfig>,
+    ) -> Result<Self, InputDeviceError> {
+        let dev = match preferred_dev {
+            Some(dev) => dev,
+            None => {
+                let host = cpal::default_host();
+                host.default_input_device()
+                    // e.g. ALSA reports a "default" device even if there is no sound card
+                    .filter(|dev| preferred_cfg.is_some() || dev.default_input_config().is_ok())
+                    .ok_or_else(|| InputDeviceError::NoDevice {
+                        host: host.id().name().to_string(),
+                    })?
+            }
+        };
+        let cfg = match preferred_cfg {
+            Some(cfg) => cfg,
+            None => dev.default_input_config()?.config(),
+        };
+        Ok(Self { dev, cfg })
     }
 
     /// Getter for audio device.
@@ -90,20 +154,38 @@ impl Debug for AudioDevAndCfg {
 }
 
 /// Sets up audio recording with the [`cpal`] library on the given audio input device.
-/// If no input device is given, it uses the default input device. Panics, if it not present.
 /// Returns the stream plus the chosen config for the device.
 ///
+/// Use [`AudioDevAndCfg::try_new`] to detect the absence of an input device beforehand.
+///
 /// Appends all audio data to the ringbuffer `latest_audio_data`.
 ///
+/// Stream errors are logged. If `on_stream_error` is present, it is invoked for each
+/// stream error too. This way, the caller can react to a disconnected device.
+///
 /// Works on Windows (WASAPI), Linux (ALSA) and MacOS (coreaudio).
 pub fn setup_audio_input_loop(
     latest_audio_data: Arc<Mutex<AllocRingBuffer<f32>>>,
     audio_dev_and_cfg: AudioDevAndCfg,
-) -> cpal::Stream {
+    on_stream_error: Option<StreamErrorCallback>,
+) -> (cpal::Stream, cpal::StreamConfig) {
+    setup_audio_input_loop_with_gains(latest_audio_data, audio_dev_and_cfg, on_stream_error, &[])
+}
+
+/// Like [`setup_audio_input_loop`] but applies a [`ChannelGain`] to each input channel.
+///
+/// The gains are applied before the channels are downmixed into the ringbuffer. `gains[i]`
+/// belongs to channel `i`; channels without an entry keep their level.
+pub fn setup_audio_input_loop_with_gains(
+    latest_audio_data: Arc<Mutex<AllocRingBuffer<f32>>>,
+    audio_dev_and_cfg: AudioDevAndCfg,
+    on_stream_error: Option<StreamErrorCallback>,
+    gains: &[ChannelGain],
+) -> (cpal::Stream, cpal::StreamConfig) {
     let dev = audio_dev_and_cfg.dev();
     let cfg = audio_dev_and_cfg.cfg();
 
-    eprintln!(
+    log::info!(
         "Using input device '{}' with config: {:?}",
         dev.name()
             .as_ref()
@@ -112,19 +194,12 @@ pub fn setup_audio_input_loop(
         cfg
     );
 
-    assert!(
-        cfg.channels == 1 || cfg.channels == 2,
-        "only supports Mono or Stereo channels!"
-    );
+    assert!(cfg.channels > 0, "there must be at least one channel!");
 
-    if cfg.sample_rate.0 != 44100 && cfg.sample_rate.0 != 48000 {
-        eprintln!(
-            "WARN: sampling rate is {}, but the crate was only tested with 44,1/48khz.",
-            cfg.sample_rate.0
-        );
-    }
-
-    let is_mono = cfg.channels == 1;
+    let channel_count = cfg.channels as usize;
+    let factors = (0..channel_count)
+        .map(|channel| ChannelGain::for_channel(gains, channel).factor())
+        .collect::<Vec<_>>();
 
     let stream = dev
         .build_input_stream(
@@ -143,22 +218,28 @@ pub fn setup_audio_input_loop(
             //  supports f32. I guess, I need to check this in the supported audio stream config too..
             move |data: &[f32], _info| {
                 let mut audio_buf = latest_audio_data.lock().unwrap();
-                // Audio buffer only contains Mono data
-                if is_mono {
-                    audio_buf.extend(data.iter().copied());
-                } else {
-                    // interleaving for stereo is LRLR (de-facto standard?)
-                    audio_buf.extend(data.chunks_exact(2).map(|vals| (vals[0] + vals[1]) / 2.0))
-                }
+                // Audio buffer only contains Mono data; all channels of a frame are averaged.
+                // interleaving is LRLR (de-facto standard?)
+                audio_buf.extend(data.chunks_exact(channel_count).map(|frame| {
+                    frame
+                        .iter()
+                        .zip(&factors)
+                        .map(|(val, factor)| val * factor)
+                        .sum::<f32>()
+                        / channel_count as f32
+                }))
             },
-            |err| {
-                eprintln!("got stream error: {:#?}", err);
+            move |err| {
+                log::error!("got stream error: {:#?}", err);
+                if let Some(on_stream_error) = &on_stream_error {
+                    on_stream_error(err);
+                }
             },
             None,
         )
         .unwrap();
 
-    stream
+    (stream, cfg.clone())
 }
 
 /// Lists all input devices for [`cpal`]. Can be used to select a device for
@@ -180,6 +261,61 @@ pub fn list_input_devs() -> Vec<(String, cpal::Device)> {
     devs
 }
 
+/// Lists all input stream configurations that the device supports.
+///
+/// Useful to let the user choose the sampling rate and the number of channels. Returns
+/// an empty vector, if the configurations can't be queried (e.g. the device was
+/// disconnected).
+pub fn supported_input_configs(dev: &cpal::Device) -> Vec<cpal::SupportedStreamConfigRange> {
+    match dev.supported_input_configs() {
+        Ok(configs) => configs.collect(),
+        Err(e) => {
+            log::warn!("Can't query the supported input configs: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Returns the supported input stream config of the device that matches the desired
+/// number of channels and sampling rate best. See [`best_matching_config`].
+pub fn best_matching_input_config(
+    dev: &cpal::Device,
+    channels: u16,
+    sample_rate: u32,
+) -> Option<cpal::StreamConfig> {
+    best_matching_config(&supported_input_configs(dev), channels, sample_rate)
+}
+
+/// Picks the config that matches the desired number of channels and sampling rate best.
+///
+/// A matching number of channels is more important than the sampling rate. Configs with
+/// the `f32` sample format are preferred. If no range contains the desired sampling rate,
+/// the nearest supported sampling rate is used.
+pub fn best_matching_config(
+    configs: &[cpal::SupportedStreamConfigRange],
+    channels: u16,
+    sample_rate: u32,
+) -> Option<cpal::StreamConfig> {
+    configs
+        .iter()
+        .map(|cfg| {
+            let nearest_sample_rate =
+                sample_rate.clamp(cfg.min_sample_rate().0, cfg.max_sample_rate().0);
+            let score = (
+                cfg.channels() != channels,
+                cfg.sample_format() != cpal::SampleFormat::F32,
+                nearest_sample_rate.abs_diff(sample_rate),
+            );
+            (score, cfg, nearest_sample_rate)
+        })
+        .min_by_key(|(score, _, _)| *score)
+        .map(|(_, cfg, nearest_sample_rate)| {
+            (*cfg)
+                .with_sample_rate(cpal::SampleRate(nearest_sample_rate))
+                .config()
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -191,4 +327,57 @@ mod tests {
             .map(|(n, d)| (n, d.default_input_config()))
             .collect::<Vec<_>>());
     }
+
+    #[test]
+    fn test_best_matching_config() {
+        use cpal::{SampleFormat, SampleRate, SupportedBufferSize, SupportedStreamConfigRange};
+        let range = |channels, min, max, format| {
+            SupportedStreamConfigRange::new(
+                channels,
+                SampleRate(min),
+                SampleRate(max),
+                SupportedBufferSize::Unknown,
+                format,
+            )
+        };
+        let configs = [
+            range(6, 44100, 48000, SampleFormat::F32),
+            range(2, 44100, 44100, SampleFormat::I16),
+            range(2, 8000, 48000, SampleFormat::F32),
+            range(1, 16000, 16000, SampleFormat::F32),
+        ];
+
+        let cfg = best_matching_config(&configs, 2, 48000).unwrap();
+        assert_eq!((cfg.channels, cfg.sample_rate.0), (2, 48000));
+        // nearest sampling rate of a mono config
+        let cfg = best_matching_config(&configs, 1, 44100).unwrap();
+        assert_eq!((cfg.channels, cfg.sample_rate.0), (1, 16000));
+        let cfg = best_matching_config(&configs, 6, 96000).unwrap();
+        assert_eq!((cfg.channels, cfg.sample_rate.0), (6, 48000));
+        // no config with 4 channels: the sample format and the sampling rate decide
+        let cfg = best_matching_config(&configs, 4, 44100).unwrap();
+        assert_eq!((cfg.channels, cfg.sample_rate.0), (6, 44100));
+
+        assert!(best_matching_config(&[], 2, 48000).is_none());
+    }
+
+    /// This test works, if it doesn't panic, i.e. also on machines without input device.
+    #[test]
+    fn test_try_new_without_preferred_dev() {
+        match AudioDevAndCfg::try_new(None, None) {
+            Ok(dev_and_cfg) => {
+                assert!(dev_and_cfg.cfg().channels > 0);
+                assert!(dev_and_cfg.cfg().sample_rate.0 > 0);
+            }
+            Err(InputDeviceError::NoDevice { host }) => {
+                assert!(!host.is_empty());
+            }
+            Err(e @ InputDeviceError::NoDefaultConfig(_)) => {
+                assert!(e.source().is_some());
+                assert!(e
+                    .to_string()
+                    .starts_with("The audio input device has no default config"));
+            }
+        }
+    }
 }
@@ -0,0 +1,141 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Loudness meter that approximates the momentary loudness of ITU-R BS.1770 (LUFS), e.g.
+//! to level content. Requires the `filters` feature.
+//!
+//! **This is an approximation and not a certified BS.1770 meter.** The K-weighting is
+//! approximated (see [`KWeighting`]) and there is no gating, which is only relevant for
+//! the integrated loudness.
+
+use crate::filters::KWeighting;
+use std::collections::VecDeque;
+
+/// Duration of the sliding window of the momentary loudness.
+const MOMENTARY_WINDOW_S: f32 = 0.4;
+
+/// Measures the momentary loudness of a stream of mono audio data: the K-weighted mean
+/// square of the latest 400ms in LUFS.
+///
+/// A full scale 1 kHz sine reads approx. -3 LUFS.
+#[derive(Debug, Clone)]
+pub struct LoudnessMeter {
+    filter: KWeighting,
+    /// Squares of the K-weighted samples of the sliding window.
+    squares: VecDeque<f32>,
+    /// Sum of `squares`.
+    sum: f64,
+    window_len: usize,
+}
+
+impl LoudnessMeter {
+    /// Creates a new meter for audio data with the given sampling rate.
+    pub fn new(sample_rate: f32) -> Self {
+        let window_len = (MOMENTARY_WINDOW_S * sample_rate).round() as usize;
+        Self {
+            filter: KWeighting::new(sample_rate),
+            squares: VecDeque::with_capacity(window_len),
+            sum: 0.0,
+            window_len,
+        }
+    }
+
+    /// Adds new samples to the sliding window. Only pass samples that have not been passed
+    /// before, as the K-weighting filter is stateful.
+    pub fn push(&mut self, samples: &[f32]) {
+        for sample in self.filter.process(samples) {
+            let square = sample * sample;
+            self.squares.push_back(square);
+            self.sum += square as f64;
+            if self.squares.len() > self.window_len {
+                self.sum -= self.squares.pop_front().unwrap() as f64;
+            }
+        }
+        // rounding errors of the running sum must not result in a negative value
+        self.sum = self.sum.max(0.0);
+    }
+
+    /// Momentary loudness in LUFS. Returns [`f64::NEG_INFINITY`] for silence. Until 400ms
+    /// of audio data were pushed, the mean square of all pushed samples is used.
+    pub fn momentary_lufs(&self) -> f64 {
+        if self.squares.is_empty() {
+            return f64::NEG_INFINITY;
+        }
+        let mean_square = self.sum / self.squares.len() as f64;
+        -0.691 + 10.0 * mean_square.log10()
+    }
+}
+
+/// Momentary loudness in LUFS of the latest 400ms of the audio data, e.g. of the audio
+/// history in a transform function of the live window. See [`LoudnessMeter`].
+///
+/// The audio data before the latest 400ms (up to 100ms) is used to settle the filter.
+pub fn momentary_lufs(audio_data: &[f32], sample_rate: f32) -> f64 {
+    let len = ((MOMENTARY_WINDOW_S + 0.1) * sample_rate).round() as usize;
+    let audio_data = &audio_data[audio_data.len().saturating_sub(len)..];
+    let settle_len = audio_data
+        .len()
+        .saturating_sub((MOMENTARY_WINDOW_S * sample_rate).round() as usize);
+    let mut meter = LoudnessMeter::new(sample_rate);
+    meter.filter.process(&audio_data[..settle_len]);
+    meter.push(&audio_data[settle_len..]);
+    meter.momentary_lufs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(frequency: f32, amplitude: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| i as f32 / 48000.0)
+            .map(|t| (t * frequency * 2.0 * std::f32::consts::PI).sin() * amplitude)
+            .collect()
+    }
+
+    #[test]
+    fn test_loudness_meter_full_scale_sine() {
+        let mut meter = LoudnessMeter::new(48000.0);
+        assert_eq!(meter.momentary_lufs(), f64::NEG_INFINITY);
+        meter.push(&sine(997.0, 1.0, 48000));
+        assert!((meter.momentary_lufs() - -3.01).abs() < 0.3);
+    }
+
+    #[test]
+    fn test_loudness_meter_sliding_window() {
+        let mut meter = LoudnessMeter::new(48000.0);
+        meter.push(&sine(997.0, 1.0, 48000));
+        // -20 dB after the window is filled with quieter audio data
+        meter.push(&sine(997.0, 0.1, 48000));
+        assert!((meter.momentary_lufs() - -23.01).abs() < 0.3);
+        meter.push(&[0.0; 48000]);
+        assert!(meter.momentary_lufs() < -100.0);
+    }
+
+    #[test]
+    fn test_momentary_lufs_of_history() {
+        let mut audio_data = vec![0.0; 48000];
+        audio_data.extend(sine(997.0, 0.1, 48000));
+        assert!((momentary_lufs(&audio_data, 48000.0) - -23.01).abs() < 0.3);
+    }
+}
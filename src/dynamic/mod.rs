@@ -29,5 +29,10 @@ SOFTWARE.
 //! **Its recommended to execute all functions here only with `--release`-flag. Otherwise
 //! the demo might run really slow.
 
+pub mod ambient;
+pub mod dummy_input;
 pub mod live_input;
+#[cfg(feature = "filters")]
+pub mod loudness;
+pub mod smoothing;
 pub mod window_top_btm;
@@ -0,0 +1,221 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Smoothing of live data over consecutive frames, e.g. of a live spectrum.
+//!
+//! The smoothing is specified as time constant instead of a fixed factor per frame.
+//! This way, it looks the same, no matter if the GUI runs at 60 or 144 FPS.
+
+use std::time::{Duration, Instant};
+
+/// Returns the factor by that a value decays during `frame_delta` for an exponential
+/// decay with the given time constant, i.e. `e^(-frame_delta / time_constant)`.
+pub fn decay_per_frame(time_constant: Duration, frame_delta: Duration) -> f64 {
+    if time_constant.is_zero() {
+        return 0.0;
+    }
+    (-frame_delta.as_secs_f64() / time_constant.as_secs_f64()).exp()
+}
+
+/// Peak-hold smoothing with exponential decay.
+///
+/// Each value is the maximum of the new value and the decayed old value. Rising values
+/// show up immediately, falling values fade out. The decay per frame is calculated from the
+/// measured time between two frames.
+#[derive(Debug, Clone)]
+pub struct PeakDecaySmoother {
+    time_constant: Duration,
+    values: Vec<f64>,
+    last_update: Option<Instant>,
+}
+
+impl PeakDecaySmoother {
+    /// Creates a new smoother. After `time_constant_ms` milliseconds, a value has decayed
+    /// to `1/e` (approx. 37%) of its peak.
+    pub fn new(time_constant_ms: f64) -> Self {
+        assert!(
+            time_constant_ms >= 0.0,
+            "time_constant_ms must not be negative!"
+        );
+        Self {
+            time_constant: Duration::from_secs_f64(time_constant_ms / 1000.0),
+            values: Vec::new(),
+            last_update: None,
+        }
+    }
+
+    /// Creates a new smoother whose peaks fall at a constant rate in dB per second, e.g.
+    /// 20.0 for -20 dB/s. The values must be linear magnitudes, not dB.
+    ///
+    /// A constant fall in dB is an exponential decay of the linear magnitude with the time
+    /// constant `20 / (db_per_second * ln(10))` seconds.
+    pub fn from_db_per_second(db_per_second: f64) -> Self {
+        assert!(db_per_second > 0.0, "db_per_second must be positive!");
+        let time_constant_s = 20.0 / (db_per_second * std::f64::consts::LN_10);
+        Self::new(time_constant_s * 1000.0)
+    }
+
+    /// Combines the latest values with the decayed previous values and returns the result.
+    /// Uses the time since the previous call as frame delta.
+    pub fn smooth(&mut self, latest: &[f64]) -> &[f64] {
+        let now = Instant::now();
+        let frame_delta = self
+            .last_update
+            .map_or(Duration::ZERO, |last_update| now - last_update);
+        self.last_update = Some(now);
+        self.smooth_with_delta(latest, frame_delta)
+    }
+
+    /// Like [`Self::smooth`] but with an explicit frame delta.
+    pub fn smooth_with_delta(&mut self, latest: &[f64], frame_delta: Duration) -> &[f64] {
+        let decay = decay_per_frame(self.time_constant, frame_delta);
+        // the number of values can change, e.g. if the FFT size changes
+        self.values.resize(latest.len(), 0.0);
+        self.values
+            .iter_mut()
+            .zip(latest.iter())
+            .for_each(|(old, new)| *old = new.max(*old * decay));
+        &self.values
+    }
+}
+
+/// Attack/release ballistics of a level, e.g. as building block of a custom level meter.
+///
+/// The level follows the target with separate time constants for rising and falling
+/// targets: `level += (target - level) * coeff`. A short attack and a long release let
+/// the level rise quickly and fall slowly. The live window has no built-in level meter;
+/// [`crate::dynamic::ambient`] uses the ballistics for the brightness of its color.
+#[derive(Debug, Clone)]
+pub struct LevelBallistics {
+    attack: Duration,
+    release: Duration,
+    level: f64,
+    last_update: Option<Instant>,
+}
+
+impl LevelBallistics {
+    /// Creates new ballistics. After `attack_ms` (or `release_ms`) milliseconds, the level
+    /// has covered `1 - 1/e` (approx. 63%) of the distance to a rising (or falling) target.
+    pub fn new(attack_ms: f64, release_ms: f64) -> Self {
+        assert!(attack_ms >= 0.0, "attack_ms must not be negative!");
+        assert!(release_ms >= 0.0, "release_ms must not be negative!");
+        Self {
+            attack: Duration::from_secs_f64(attack_ms / 1000.0),
+            release: Duration::from_secs_f64(release_ms / 1000.0),
+            level: 0.0,
+            last_update: None,
+        }
+    }
+
+    /// The current level.
+    pub const fn level(&self) -> f64 {
+        self.level
+    }
+
+    /// Moves the level towards `target` and returns the new level. Uses the time since
+    /// the previous call as frame delta.
+    pub fn update(&mut self, target: f64) -> f64 {
+        let now = Instant::now();
+        let frame_delta = self
+            .last_update
+            .map_or(Duration::ZERO, |last_update| now - last_update);
+        self.last_update = Some(now);
+        self.update_with_delta(target, frame_delta)
+    }
+
+    /// Like [`Self::update`] but with an explicit frame delta.
+    pub fn update_with_delta(&mut self, target: f64, frame_delta: Duration) -> f64 {
+        let time_constant = if target > self.level {
+            self.attack
+        } else {
+            self.release
+        };
+        let coeff = 1.0 - decay_per_frame(time_constant, frame_delta);
+        self.level += (target - self.level) * coeff;
+        self.level
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decay_per_frame_is_independent_of_frame_rate() {
+        let time_constant = Duration::from_millis(40);
+        let second = Duration::from_secs(1);
+        let decay_60_fps = decay_per_frame(time_constant, second / 60).powi(60);
+        let decay_144_fps = decay_per_frame(time_constant, second / 144).powi(144);
+        assert!((decay_60_fps - decay_144_fps).abs() < 1e-12);
+        assert!((decay_per_frame(time_constant, time_constant) - (-1.0_f64).exp()).abs() < 1e-12);
+        assert_eq!(decay_per_frame(Duration::ZERO, second), 0.0);
+    }
+
+    #[test]
+    fn test_peak_decay_smoother() {
+        let mut smoother = PeakDecaySmoother::new(100.0);
+        assert_eq!(
+            smoother.smooth_with_delta(&[1.0, 0.0], Duration::ZERO),
+            &[1.0, 0.0]
+        );
+        let values = smoother
+            .smooth_with_delta(&[0.0, 0.5], Duration::from_millis(100))
+            .to_vec();
+        assert!((values[0] - (-1.0_f64).exp()).abs() < 1e-12);
+        assert_eq!(values[1], 0.5);
+    }
+
+    #[test]
+    fn test_peak_decay_smoother_db_per_second() {
+        let mut smoother = PeakDecaySmoother::from_db_per_second(20.0);
+        smoother.smooth_with_delta(&[1.0], Duration::ZERO);
+        // -20 dB after one second, no matter how many frames
+        (0..60).for_each(|_| {
+            smoother.smooth_with_delta(&[0.0], Duration::from_secs(1) / 60);
+        });
+        let value = smoother.smooth_with_delta(&[0.0], Duration::ZERO)[0];
+        assert!((value - 0.1).abs() < 1e-6);
+        let value = smoother.smooth_with_delta(&[0.0], Duration::from_millis(500))[0];
+        assert!((20.0 * value.log10() + 30.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_level_ballistics() {
+        let mut meter = LevelBallistics::new(10.0, 300.0);
+        let frame = Duration::from_millis(10);
+        // rises quickly
+        let level = meter.update_with_delta(1.0, frame);
+        assert!((level - (1.0 - (-1.0_f64).exp())).abs() < 1e-12);
+        (0..10).for_each(|_| {
+            meter.update_with_delta(1.0, frame);
+        });
+        assert!(meter.level() > 0.99);
+        // falls slowly
+        let level = meter.update_with_delta(0.0, frame);
+        assert!(level > 0.95);
+        // instant with a time constant of zero
+        let mut meter = LevelBallistics::new(0.0, 0.0);
+        assert_eq!(meter.update_with_delta(0.5, frame), 0.5);
+        assert_eq!(meter.update_with_delta(0.0, frame), 0.0);
+    }
+}
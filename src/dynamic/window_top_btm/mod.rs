@@ -26,31 +26,59 @@ SOFTWARE.
 //! diagram of transformed data, such as a lowpass filter or a a frequency spectrum.
 //!
 //! It uses the [`minifb`] crate to display GUI windows.
-use crate::dynamic::live_input::{setup_audio_input_loop, AudioDevAndCfg};
+use crate::dynamic::live_input::{setup_audio_input_loop_with_gains, AudioDevAndCfg};
+use crate::dynamic::window_top_btm::timing::{FrameTimingCollector, FrameTimingSummary};
 use crate::dynamic::window_top_btm::visualize_minifb::{
-    get_drawing_areas, setup_window, DEFAULT_H, DEFAULT_W,
+    draw_btm_chart, draw_top_chart, get_drawing_areas_stacked, setup_window_stacked, BtmScale,
+    TriggerMode, WindowConfig, DEFAULT_H, DEFAULT_W,
 };
+use crate::spectrum::plotters_png_file::spectrum_static_plotters_png_visualize;
+use crate::util::dsp::latest_n;
+use crate::util::music::nearest_note;
+use crate::waveform::png_file::waveform_static_png_visualize_f32;
+use crate::Channels;
 use cpal::traits::StreamTrait;
 
-use minifb::Key;
-use plotters::chart::ChartContext;
+use minifb::{Key, KeyRepeat, Window};
+use plotters::chart::{ChartContext, SeriesAnno};
 use plotters::coord::cartesian::Cartesian2d;
 use plotters::coord::types::RangedCoordf64;
+use plotters::element::{PathElement, Text};
 use plotters::prelude::BitMapBackend;
 use plotters::series::LineSeries;
-use plotters::style::{BLACK, CYAN};
+use plotters::style::{IntoFont, RGBColor, CYAN, GREEN, MAGENTA, YELLOW};
 use plotters_bitmap::bitmap_pixel::BGRXPixel;
 use ringbuffer::{AllocRingBuffer, RingBuffer};
+use spectrum_analyzer::scaling::divide_by_N;
+use spectrum_analyzer::windows::hann_window;
+use spectrum_analyzer::{samples_fft_to_spectrum, FrequencyLimit};
 use std::borrow::{Borrow, BorrowMut};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
 use std::ops::Range;
+use std::path::Path;
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 /// Smooth refresh rate on 144 Hz displays.
 const REFRESH_RATE: f64 = 144.0;
 const REFRESH_S: f64 = 1.0 / REFRESH_RATE;
+/// Factor by that the upper chart is zoomed in/out per key press.
+const ZOOM_STEP: f64 = 1.25;
+/// Maximum zoom factor of the upper chart.
+const MAX_ZOOM: f64 = 1000.0;
+/// Fraction of the visible time range by that the upper chart is panned per key press.
+const PAN_STEP: f64 = 0.1;
+/// Number of latest samples of which [`TransformFn::Spectrum`] is calculated; ~46ms at 44.1kHz.
+const LIVE_FFT_SIZE: usize = 2048;
+/// Colors of the devices of [`open_window_connect_audio_stacked`], if there are several.
+const INPUT_COLORS: [RGBColor; 4] = [CYAN, MAGENTA, YELLOW, GREEN];
+/// Color of the guide lines in the lower chart. See [`WindowConfig::btm_guide_lines`].
+const GUIDE_LINE_COLOR: RGBColor = RGBColor(150, 150, 0);
 
 pub mod pixel_buf;
+pub mod timing;
 pub mod visualize_minifb;
 
 /// Parameter type for [`open_window_connect_audio`]. Describes how the audio data shall
@@ -66,7 +94,16 @@ pub enum TransformFn<'a> {
     ///
     /// Functions takes amplitude values and transforms them to a new amplitude value.
     /// It gets the sampling rate as second argument.
+    ///
+    /// The returned values are spread over the whole audio history. If the transformation
+    /// doesn't preserve the number of samples, e.g. trims the data, use
+    /// [`Self::BasicWithSpan`].
     Basic(fn(&[f32], f32) -> Vec<f32>),
+    /// Like [`Self::Basic`] but the function also returns the time span in seconds that the
+    /// returned values cover, ending with the latest sample. Useful for transformations that
+    /// decimate or trim the data, e.g. to a span of `values.len() / sampling_rate` seconds
+    /// for the latest values only.
+    BasicWithSpan(fn(&[f32], f32) -> (Vec<f32>, f64)),
     /// Use this, when the x-axis is different than for the original data. For example,
     /// if you want to display a spectrum.
     ///
@@ -75,6 +112,55 @@ pub enum TransformFn<'a> {
     /// It gets the sampling rate as second argument.
     #[allow(clippy::complexity)]
     Complex(&'a dyn Fn(&[f32], f32) -> Vec<(f64, f64)>),
+    /// Built-in spectrum of the latest 2048 samples (FFT with Hann window), like
+    /// [`Self::Complex`] with a spectrum function. The analyzed frequency range and the
+    /// x-axis of the lower chart are [`WindowConfig::frequency_limit`].
+    Spectrum,
+    /// Draws the output of several transform functions as `(function, color, label)` into
+    /// the lower chart, e.g. the original and the filtered audio data, with a legend of the
+    /// labels (see [`WindowConfig::show_legend`]).
+    ///
+    /// All functions share the axes of the lower chart, so they should have the same kind
+    /// of x-axis, i.e. either all [`Self::Basic`] (or [`Self::BasicWithSpan`]) or all with
+    /// `(x, y)` pairs.
+    Overlay(Vec<(Self, RGBColor, &'a str)>),
+}
+
+impl TransformFn<'_> {
+    /// Whether this is or contains [`Self::Spectrum`].
+    fn has_spectrum(&self) -> bool {
+        match self {
+            Self::Spectrum => true,
+            Self::Overlay(fncs) => fncs.iter().any(|(fnc, _, _)| fnc.has_spectrum()),
+            _ => false,
+        }
+    }
+
+    /// Whether this is or contains a function with `(x, y)` pairs, i.e. whether the lower
+    /// chart supports a [`BtmScale`].
+    fn has_pairs(&self) -> bool {
+        match self {
+            Self::Basic(_) | Self::BasicWithSpan(_) => false,
+            Self::Complex(_) | Self::Spectrum => true,
+            Self::Overlay(fncs) => fncs.iter().any(|(fnc, _, _)| fnc.has_pairs()),
+        }
+    }
+}
+
+/// Output of a transform function; one series in the lower chart.
+enum TransformOutput {
+    /// Amplitudes over time and the time span in seconds that they cover; output of
+    /// [`TransformFn::Basic`] and [`TransformFn::BasicWithSpan`].
+    Waveform(Vec<f32>, f64),
+    /// `(x, y)` pairs, e.g. a spectrum.
+    Pairs(Vec<(f64, f64)>),
+}
+
+/// A series of the lower chart with its color and its label in the legend.
+struct Series<'a> {
+    output: TransformOutput,
+    color: RGBColor,
+    label: Option<&'a str>,
 }
 
 /// Starts the audio recording via `cpal` on the given audio device (or the default input device),
@@ -82,22 +168,40 @@ pub enum TransformFn<'a> {
 /// wave form (live/real time). The lower graph can be customized, to show for example a
 /// spectrum or the lowpassed data.
 ///
-/// This operation is blocking. It returns, when the GUI window is closed.
+/// This operation is blocking. It returns, when the GUI window is closed. If
+/// [`WindowConfig::collect_timing`] is set, it returns a summary of the timing of all
+/// frames. If [`WindowConfig::run_for`] is set but [`WindowConfig::export_dir`] isn't a
+/// directory, it logs an error and returns `None` without opening the window.
+///
+/// The time axis of the upper graph can be zoomed with `+`/`-` and panned with the
+/// left and right arrow keys. `R` resets the view. For [`TransformFn::Complex`] and
+/// [`TransformFn::Spectrum`], `C` writes
+/// the `(x, y)` pairs of the lower chart of the current frame (e.g. the spectrum) to a
+/// timestamped CSV file in [`WindowConfig::export_dir`]. `P` exports the current frame
+/// as labeled spectrum chart (x in Hz) to a timestamped PNG file in the same directory,
+/// like [`spectrum_static_plotters_png_visualize`]. For functions with `(x, y)` pairs, `L`
+/// toggles a logarithmic x-axis of the lower chart and `D` a y-axis in dB (see
+/// [`WindowConfig::btm_scale`]). With [`WindowConfig::run_for`], the window records for
+/// the given duration, writes the recording as waveform PNG and closes automatically.
 ///
 /// **This operation is expensive and will be very laggy in "Debug" builds!**
 ///
+/// To test transform functions without audio hardware, feed a ringbuffer with a
+/// [`crate::dynamic::dummy_input::DummyInput`] instead.
+///
 /// # Parameters
 /// - `name` Name of the GUI window
 /// - `preferred_height` Preferred height of GUI window. Default is [`DEFAULT_H`].
 /// - `preferred_width` Preferred height of GUI window. Default is [`DEFAULT_W`].
 /// - `preferred_x_range` Preferred range for the x-axis of the lower (=custom) diagram.
-///                       If no value is present, the same value as for the upper diagram is used.
+///                       Ignored for [`TransformFn::Spectrum`]. If no value is present, the same value as for the upper diagram is used.
 /// - `preferred_y_range` Preferred range for the y-axis of the lower (=custom) diagram.
 ///                       If no value is present, the same value as for the upper diagram is used.
 /// - `x_desc` Description for the x-axis of the lower (=custom) diagram.
 /// - `y_desc` Description for the y-axis of the lower (=custom) diagram.
 /// - `preferred_input_dev` See [`AudioDevAndCfg`].
 /// - `audio_data_transform_fn` See [`open_window_connect_audio`].
+/// - `window_cfg` See [`WindowConfig`].
 #[allow(clippy::too_many_arguments)]
 pub fn open_window_connect_audio(
     name: &str,
@@ -109,17 +213,122 @@ pub fn open_window_connect_audio(
     y_desc: &str,
     input_dev_and_cfg: AudioDevAndCfg,
     audio_data_transform_fn: TransformFn,
-) {
-    let sample_rate = input_dev_and_cfg.cfg().sample_rate.0 as f32;
-    let latest_audio_data = init_ringbuffer(sample_rate as usize);
-    let audio_buffer_len = latest_audio_data.lock().unwrap().len();
-    let stream = setup_audio_input_loop(latest_audio_data.clone(), input_dev_and_cfg);
+    window_cfg: WindowConfig,
+) -> Option<FrameTimingSummary> {
+    open_window_connect_audio_stacked(
+        name,
+        preferred_height,
+        preferred_width,
+        preferred_x_range,
+        preferred_y_range,
+        x_desc,
+        y_desc,
+        vec![input_dev_and_cfg],
+        audio_data_transform_fn,
+        window_cfg,
+    )
+}
+
+/// Like [`open_window_connect_audio`] but records several audio devices at once, e.g. to
+/// compare two microphones.
+///
+/// Each device gets its own stream, ringbuffer and upper chart; the upper charts are
+/// stacked on top of each other (see [`visualize_minifb::get_drawing_areas_stacked`]) and
+/// share the time axis.
+///
+/// The transform function is applied to the audio data of each device and all results are
+/// drawn into the shared lower chart. With more than one device, the waveform and the
+/// results of each device have their own color and are labeled "input 1", "input 2", ...
+/// in the legend. Functions of a [`TransformFn::Overlay`] keep their colors and labels.
+///
+/// The x-axes are derived from the first device, e.g. the range of the built-in spectrum,
+/// and [`WindowConfig::run_for`] exports the recording of the first device.
+#[allow(clippy::too_many_arguments)]
+pub fn open_window_connect_audio_stacked(
+    name: &str,
+    preferred_height: Option<usize>,
+    preferred_width: Option<usize>,
+    preferred_x_range: Option<Range<f64>>,
+    preferred_y_range: Option<Range<f64>>,
+    x_desc: &str,
+    y_desc: &str,
+    input_devs_and_cfgs: Vec<AudioDevAndCfg>,
+    audio_data_transform_fn: TransformFn,
+    window_cfg: WindowConfig,
+) -> Option<FrameTimingSummary> {
+    assert!(
+        !input_devs_and_cfgs.is_empty(),
+        "there must be at least one input device!"
+    );
+    // fail before recording instead of losing the recording at the end
+    if window_cfg.run_for.is_some() && !window_cfg.export_dir.is_dir() {
+        log::error!(
+            "Can't record for run_for: {} is not a directory",
+            window_cfg.export_dir.display()
+        );
+        return None;
+    }
+    let is_stacked = input_devs_and_cfgs.len() > 1;
+    let labels = (1..=input_devs_and_cfgs.len())
+        .map(|i| format!("input {}", i))
+        .collect::<Vec<_>>();
+    let inputs = input_devs_and_cfgs
+        .into_iter()
+        .enumerate()
+        .map(|(i, input_dev_and_cfg)| {
+            let latest_audio_data =
+                init_ringbuffer(window_cfg.audio_buffer_len(input_dev_and_cfg.cfg().sample_rate.0));
+            let audio_buffer_len = latest_audio_data.lock().unwrap().len();
+            let (stream, cfg) = setup_audio_input_loop_with_gains(
+                latest_audio_data.clone(),
+                input_dev_and_cfg,
+                None,
+                &window_cfg.channel_gains,
+            );
+            Input {
+                stream,
+                latest_audio_data,
+                audio_buffer_len,
+                sample_rate: cfg.sample_rate.0 as f32,
+                color: if is_stacked {
+                    INPUT_COLORS[i % INPUT_COLORS.len()]
+                } else {
+                    CYAN
+                },
+                label: if is_stacked {
+                    Some(labels[i].as_str())
+                } else {
+                    None
+                },
+            }
+        })
+        .collect::<Vec<_>>();
+    let first_input = &inputs[0];
+    let sample_rate = first_input.sample_rate;
+    let audio_buffer_len = first_input.audio_buffer_len;
     // This will be 1/44100 or 1/48000; the two most common sampling rates.
     let time_per_sample = 1.0 / sample_rate as f64;
+    // distance of two samples on the x-axis of the upper chart; in seconds or samples
+    let x_per_sample = window_cfg.x_axis_unit.x_per_sample(time_per_sample);
+
+    // the x-axis of the built-in spectrum always matches the analyzed frequency range
+    let preferred_x_range = if audio_data_transform_fn.has_spectrum() {
+        Some(spectrum_x_range(window_cfg.frequency_limit, sample_rate))
+    } else {
+        preferred_x_range
+    };
 
     // start recording; audio will be continuously stored in "latest_audio_data"
-    stream.play().unwrap();
-    let (mut window, top_cs, btm_cs, mut pixel_buf) = setup_window(
+    inputs.iter().for_each(|input| input.stream.play().unwrap());
+    let recording_begin = Instant::now();
+    // linear ranges of the lower chart; converted, whenever the scales of the chart change
+    let btm_x_range = preferred_x_range
+        .clone()
+        .unwrap_or(-(audio_buffer_len as f64 * x_per_sample)..0.0);
+    let btm_y_range = preferred_y_range
+        .clone()
+        .unwrap_or_else(|| window_cfg.y_range_top());
+    let (mut window, mut top_css, mut btm_cs, mut pixel_buf) = setup_window_stacked(
         name,
         preferred_height,
         preferred_width,
@@ -129,51 +338,198 @@ pub fn open_window_connect_audio(
         y_desc,
         audio_buffer_len,
         time_per_sample,
+        inputs.len(),
+        &window_cfg,
     );
     window.limit_update_rate(Some(Duration::from_secs_f64(REFRESH_S)));
 
+    let mut top_view = TimeAxisView::new(audio_buffer_len as f64 * x_per_sample);
+    let has_pairs = audio_data_transform_fn.has_pairs();
+    let mut btm_scale = if !has_pairs {
+        BtmScale::default()
+    } else if window_cfg
+        .btm_scale
+        .is_applicable(&btm_x_range, &btm_y_range)
+    {
+        window_cfg.btm_scale
+    } else {
+        log::warn!(
+            "Can't apply {:?} to the ranges of the lower chart; using linear scales",
+            window_cfg.btm_scale
+        );
+        BtmScale::default()
+    };
+    // the setup draws the lower chart with linear scales
+    let mut drawn_btm_scale = BtmScale::default();
+    let mut timing = FrameTimingCollector::default();
+
     // GUI refresh loop; CPU-limited by "window.limit_update_rate"
     while window.is_open() {
         if window.is_key_down(Key::Escape) {
             break;
         }
+        let frame_begin = Instant::now();
+        let top_view_changed = top_view.handle_keys(&window);
+        if has_pairs {
+            window
+                .get_keys_pressed(KeyRepeat::No)
+                .iter()
+                .for_each(|key| {
+                    let mut scale = btm_scale;
+                    scale.apply_key(*key);
+                    // e.g. no dB for a y-range that is already in dB; keep the scales
+                    if scale.is_applicable(&btm_x_range, &btm_y_range) {
+                        btm_scale = scale;
+                    } else {
+                        log::warn!("Can't apply {:?} to the ranges of the lower chart", scale);
+                    }
+                });
+        }
 
-        let (top_drawing_area, btm_drawing_area) = get_drawing_areas(
+        let (top_drawing_areas, btm_drawing_area) = get_drawing_areas_stacked(
             pixel_buf.borrow_mut(),
             preferred_width.unwrap_or(DEFAULT_W),
             preferred_height.unwrap_or(DEFAULT_H),
+            inputs.len(),
+            &window_cfg,
         );
 
-        let top_chart = top_cs.clone().restore(&top_drawing_area);
-        let btm_chart = btm_cs.clone().restore(&btm_drawing_area);
+        // scales of the lower chart toggled: axes must be redrawn
+        if btm_scale != drawn_btm_scale {
+            btm_cs = draw_btm_chart(
+                btm_drawing_area.clone(),
+                btm_x_range.clone(),
+                btm_y_range.clone(),
+                x_desc,
+                y_desc,
+                btm_scale,
+                &window_cfg,
+            );
+            drawn_btm_scale = btm_scale;
+        }
 
+        let mut btm_chart = btm_cs.clone().restore(&btm_drawing_area);
         // remove drawings from previous iteration (but keep axis etc)
-        top_chart.plotting_area().fill(&BLACK).borrow();
-        btm_chart.plotting_area().fill(&BLACK).borrow();
-
-        // lock released immediately after oneliner
-        let latest_audio_data = latest_audio_data.clone().lock().unwrap().to_vec();
-        fill_chart_waveform_over_time(
-            top_chart,
-            &latest_audio_data,
-            time_per_sample,
-            audio_buffer_len,
+        btm_chart
+            .plotting_area()
+            .fill(&window_cfg.background)
+            .borrow();
+        draw_guide_lines(
+            &mut btm_chart,
+            &window_cfg.btm_guide_lines,
+            window_cfg.font(),
         );
-        if let TransformFn::Basic(fnc) = audio_data_transform_fn {
-            let data = fnc(&latest_audio_data, sample_rate);
-            fill_chart_waveform_over_time(btm_chart, &data, time_per_sample, audio_buffer_len);
-        } else if let TransformFn::Complex(fnc) = audio_data_transform_fn {
-            let data = fnc(&latest_audio_data, sample_rate);
-            fill_chart_complex_fnc(btm_chart, data);
-        } else {
-            // required for compilation
-            drop(btm_chart);
-            panic!("invalid transform fn variant");
+
+        let mut transform_duration = Duration::ZERO;
+        let mut series = Vec::new();
+        let mut first_audio_data = Vec::new();
+        for ((input, top_drawing_area), top_cs) in
+            inputs.iter().zip(top_drawing_areas).zip(top_css.iter_mut())
+        {
+            // zoom or pan happened: axes must be redrawn
+            if top_view_changed {
+                top_drawing_area.fill(&window_cfg.background).unwrap();
+                *top_cs = draw_top_chart(top_drawing_area.clone(), top_view.x_range(), &window_cfg);
+            }
+            let mut top_chart = top_cs.clone().restore(&top_drawing_area);
+            top_chart
+                .plotting_area()
+                .fill(&window_cfg.background)
+                .borrow();
+
+            // lock released immediately after oneliner
+            let latest_audio_data = input.latest_audio_data.lock().unwrap().to_vec();
+            // distance of two samples on the x-axis; the devices may have different rates
+            let x_per_sample = window_cfg
+                .x_axis_unit
+                .x_per_sample(1.0 / input.sample_rate as f64);
+            let top_audio_data = match window_cfg.trigger {
+                TriggerMode::Off => None,
+                TriggerMode::RisingEdge { level } => {
+                    let left_border = top_view.x_range().start;
+                    let left_border_index = latest_audio_data.len()
+                        - ((-left_border / x_per_sample).round() as usize)
+                            .min(latest_audio_data.len());
+                    find_trigger_shift(&latest_audio_data, level, left_border_index)
+                        .map(|shift| shift_right(&latest_audio_data, shift))
+                }
+            };
+            fill_chart_waveform_over_time(
+                &mut top_chart,
+                top_audio_data.as_ref().unwrap_or(&latest_audio_data),
+                input.audio_buffer_len as f64 * x_per_sample,
+                input.audio_buffer_len,
+                input.color,
+                None,
+            );
+            #[cfg(feature = "filters")]
+            if window_cfg.show_loudness {
+                let lufs =
+                    crate::dynamic::loudness::momentary_lufs(&latest_audio_data, input.sample_rate);
+                draw_corner_label(&mut top_chart, format!("{:.1} LUFS", lufs), &window_cfg);
+            }
+            let transform_begin = Instant::now();
+            apply_transform_fn(
+                &audio_data_transform_fn,
+                &latest_audio_data,
+                input.sample_rate,
+                window_cfg.frequency_limit,
+                input.color,
+                input.label,
+                &mut series,
+            );
+            transform_duration += transform_begin.elapsed();
+            if first_audio_data.is_empty() {
+                first_audio_data = latest_audio_data;
+            }
+        }
+
+        // exports the first series with (x, y) pairs
+        let pairs = series.iter().find_map(|series| match &series.output {
+            TransformOutput::Pairs(data) => Some(data),
+            TransformOutput::Waveform(..) => None,
+        });
+        if let Some(data) = pairs {
+            if window_cfg.show_peak_freq || window_cfg.show_tuner {
+                draw_peak_frequency(&mut btm_chart, data, &window_cfg);
+            }
+            if window.is_key_pressed(Key::C, KeyRepeat::No) {
+                export_csv(&window_cfg.export_dir, data);
+            }
+            if window.is_key_pressed(Key::P, KeyRepeat::No) {
+                export_spectrum_png(&window_cfg.export_dir, data);
+            }
+        }
+        for series in series {
+            match series.output {
+                TransformOutput::Waveform(data, span_s) => fill_chart_waveform_over_time(
+                    &mut btm_chart,
+                    &data,
+                    span_s / time_per_sample * x_per_sample,
+                    audio_buffer_len,
+                    series.color,
+                    series.label,
+                ),
+                TransformOutput::Pairs(data) => {
+                    let y_min = btm_chart.y_range().start;
+                    let data = data
+                        .into_iter()
+                        .filter_map(|point| btm_scale.scale_point(point, y_min))
+                        .collect();
+                    fill_chart_complex_fnc(&mut btm_chart, data, series.color, series.label)
+                }
+            }
+        }
+        let has_labels = is_stacked || matches!(audio_data_transform_fn, TransformFn::Overlay(_));
+        if has_labels && window_cfg.show_legend {
+            draw_legend(&mut btm_chart, &window_cfg);
         }
 
         // make sure that "pixel_buf" is not borrowed longer
-        drop(top_drawing_area);
+        drop(btm_chart);
         drop(btm_drawing_area);
+        let drawing_duration = frame_begin.elapsed() - transform_duration;
+        let update_begin = Instant::now();
 
         // REQUIRED to call on of the .update*()-methods, otherwise mouse and keyboard events
         // are not updated
@@ -187,38 +543,464 @@ pub fn open_window_connect_audio(
                 preferred_height.unwrap_or(DEFAULT_H),
             )
             .unwrap();
+
+        if window_cfg.collect_timing {
+            timing.add_frame(transform_duration, drawing_duration, update_begin.elapsed());
+        }
+
+        if let Some(run_for) = window_cfg.run_for {
+            if recording_begin.elapsed() >= run_for {
+                let recorded_len = (run_for.as_secs_f64() * sample_rate as f64) as usize;
+                export_waveform_png(
+                    &window_cfg.export_dir,
+                    latest_n(&first_audio_data, recorded_len),
+                );
+                break;
+            }
+        }
     }
-    stream.pause().unwrap();
+    inputs
+        .iter()
+        .for_each(|input| input.stream.pause().unwrap());
+
+    if window_cfg.collect_timing {
+        Some(timing.summary())
+    } else {
+        None
+    }
+}
+
+/// An audio device of [`open_window_connect_audio_stacked`] with its own stream and
+/// ringbuffer.
+struct Input<'a> {
+    stream: cpal::Stream,
+    latest_audio_data: Arc<Mutex<AllocRingBuffer<f32>>>,
+    audio_buffer_len: usize,
+    sample_rate: f32,
+    /// Color of the waveform and of the transformed data.
+    color: RGBColor,
+    /// Label in the legend; only if there are several devices.
+    label: Option<&'a str>,
+}
+
+/// The visible part of the time axis of the upper chart. The audio history spans
+/// several seconds but the user can zoom in and pan to inspect a shorter time range.
+/// All values are in the unit of the time axis, see [`WindowConfig::x_axis_unit`].
+#[derive(Debug, Copy, Clone)]
+struct TimeAxisView {
+    /// Duration of the whole audio history.
+    history: f64,
+    /// Zoom factor; 1.0 shows the whole history.
+    zoom: f64,
+    /// Offset of the right end of the visible time range (<= 0).
+    offset: f64,
 }
 
-/// Inits a ringbuffer on the heap and fills it with zeroes.
-fn init_ringbuffer(sampling_rate: usize) -> Arc<Mutex<AllocRingBuffer<f32>>> {
-    // Must be a power (ringbuffer requirement).
-    let mut buf = AllocRingBuffer::new((5 * sampling_rate).next_power_of_two());
+impl TimeAxisView {
+    const fn new(history: f64) -> Self {
+        Self {
+            history,
+            zoom: 1.0,
+            offset: 0.0,
+        }
+    }
+
+    /// Updates the view according to the pressed keys. Returns true, if the view changed.
+    fn handle_keys(&mut self, window: &Window) -> bool {
+        let old = (self.zoom, self.offset);
+        window
+            .get_keys_pressed(KeyRepeat::Yes)
+            .iter()
+            .for_each(|key| self.apply_key(*key));
+        old != (self.zoom, self.offset)
+    }
+
+    /// Updates the view according to a single key press.
+    fn apply_key(&mut self, key: Key) {
+        let visible = self.history / self.zoom;
+        match key {
+            Key::Equal | Key::NumPadPlus => self.zoom = (self.zoom * ZOOM_STEP).min(MAX_ZOOM),
+            Key::Minus | Key::NumPadMinus => self.zoom = (self.zoom / ZOOM_STEP).max(1.0),
+            Key::Left => self.offset -= visible * PAN_STEP,
+            Key::Right => self.offset += visible * PAN_STEP,
+            Key::R => *self = Self::new(self.history),
+            _ => {}
+        }
+        // stay within the audio history
+        let visible = self.history / self.zoom;
+        self.offset = self.offset.min(0.0).max(visible - self.history);
+    }
+
+    /// Returns the visible x-range.
+    fn x_range(&self) -> Range<f64> {
+        (self.offset - self.history / self.zoom)..self.offset
+    }
+}
+
+/// Searches backwards from `left_border_index` for the latest rising edge through `level`.
+/// Returns by how many samples the audio data must be shifted to the right (into the
+/// future), so that the trigger point lies at `left_border_index`.
+fn find_trigger_shift(audio_data: &[f32], level: f32, left_border_index: usize) -> Option<usize> {
+    (1..=left_border_index.min(audio_data.len().saturating_sub(1)))
+        .rev()
+        .find(|i| audio_data[i - 1] < level && audio_data[*i] >= level)
+        .map(|i| left_border_index - i)
+}
+
+/// Shifts the audio data by `shift` samples to the right. The oldest samples are filled
+/// with zeroes, the newest samples are dropped.
+fn shift_right(audio_data: &[f32], shift: usize) -> Vec<f32> {
+    let shift = shift.min(audio_data.len());
+    let mut shifted = vec![0.0; shift];
+    shifted.extend_from_slice(&audio_data[..audio_data.len() - shift]);
+    shifted
+}
+
+/// Number of samples of the audio history. See [`WindowConfig::history_s`].
+fn ringbuffer_len(sampling_rate: usize, history_s: f64) -> usize {
+    assert!(history_s > 0.0, "history_s must be positive!");
+    // Must be a power (ringbuffer requirement). The ringbuffer allocates exactly this.
+    ((history_s * sampling_rate as f64).ceil() as usize).next_power_of_two()
+}
+
+/// Inits a ringbuffer with `len` elements on the heap and fills it with zeroes.
+fn init_ringbuffer(len: usize) -> Arc<Mutex<AllocRingBuffer<f32>>> {
+    let mut buf = AllocRingBuffer::new(len);
     buf.fill(0.0);
     Arc::new(Mutex::new(buf))
 }
 
+/// Range of the x-axis of the lower chart for [`TransformFn::Spectrum`]. Panics, if the
+/// frequency limit is invalid for the sampling rate.
+fn spectrum_x_range(frequency_limit: FrequencyLimit, sample_rate: f32) -> Range<f64> {
+    let nyquist = sample_rate / 2.0;
+    frequency_limit
+        .verify(nyquist)
+        .unwrap_or_else(|e| panic!("invalid frequency limit: {}", e));
+    let min = frequency_limit.maybe_min().unwrap_or(0.0);
+    let max = frequency_limit.maybe_max().unwrap_or(nyquist);
+    min as f64..max as f64
+}
+
+/// Calculates the spectrum of the latest [`LIVE_FFT_SIZE`] samples for
+/// [`TransformFn::Spectrum`]. Returns `(frequency, magnitude)` pairs.
+fn live_spectrum(
+    audio_data: &[f32],
+    sample_rate: f32,
+    frequency_limit: FrequencyLimit,
+) -> Vec<(f64, f64)> {
+    let latest = latest_n(audio_data, LIVE_FFT_SIZE);
+    samples_fft_to_spectrum(
+        &hann_window(latest),
+        sample_rate as u32,
+        frequency_limit,
+        Some(&divide_by_N),
+    )
+    .unwrap()
+    .data()
+    .iter()
+    .map(|(frequency, magnitude)| (frequency.val() as f64, magnitude.val() as f64))
+    .collect()
+}
+
+/// Writes the `(x, y)` pairs to a CSV file named after the current time, e.g.
+/// `live_1700000000123.csv`, in the given directory. Errors are logged; the render loop
+/// continues.
+fn export_csv(directory: &Path, data: &[(f64, f64)]) {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |x| x.as_millis());
+    let path = directory.join(format!("live_{}.csv", millis));
+    let result = File::create(&path).and_then(|file| {
+        let mut writer = BufWriter::new(file);
+        write_csv(&mut writer, data)?;
+        writer.flush()
+    });
+    match result {
+        Ok(()) => log::info!("Wrote {} rows to {}", data.len(), path.display()),
+        Err(e) => log::error!("Can't write {}: {}", path.display(), e),
+    }
+}
+
+/// Writes the `(x, y)` pairs of the current frame as spectrum chart to a timestamped
+/// PNG file in the given directory. Logs an error, if the directory doesn't exist or if the
+/// frame can't be drawn as spectrum, i.e. if it has less than two frequencies or NaN or
+/// infinite values.
+fn export_spectrum_png(directory: &Path, data: &[(f64, f64)]) {
+    if !directory.is_dir() {
+        log::error!(
+            "Can't write PNG: {} is not a directory",
+            directory.display()
+        );
+        return;
+    }
+    // checked before the conversion, which drops NaN values
+    let spectrum = to_spectrum_map(data);
+    if spectrum.len() < 2 || data.iter().any(|(_, y)| !y.is_finite()) {
+        log::error!("Can't write PNG: the frame has less than two frequencies or invalid values");
+        return;
+    }
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |x| x.as_millis());
+    let filename = format!("live_{}.png", millis);
+    spectrum_static_plotters_png_visualize(&spectrum, &directory.to_string_lossy(), &filename);
+    log::info!("Wrote {}", directory.join(filename).display());
+}
+
+/// Writes the audio data as waveform to a timestamped PNG file in the given directory.
+/// Logs an error, if the directory doesn't exist.
+fn export_waveform_png(directory: &Path, audio_data: &[f32]) {
+    if !directory.is_dir() {
+        log::error!(
+            "Can't write PNG: {} is not a directory",
+            directory.display()
+        );
+        return;
+    }
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |x| x.as_millis());
+    let filename = format!("recording_{}.png", millis);
+    waveform_static_png_visualize_f32(
+        audio_data,
+        Channels::Mono,
+        &directory.to_string_lossy(),
+        &filename,
+    );
+    log::info!("Wrote {}", directory.join(filename).display());
+}
+
+/// Converts `(frequency, magnitude)` pairs into the format of the static spectrum
+/// functions. Frequencies are rounded to whole Hz; negative frequencies are skipped.
+/// If two pairs end up at the same frequency, the higher magnitude wins.
+fn to_spectrum_map(data: &[(f64, f64)]) -> BTreeMap<u32, f32> {
+    let mut map = BTreeMap::new();
+    data.iter()
+        .filter(|(fr, _)| *fr >= 0.0)
+        .for_each(|(fr, val)| {
+            let entry = map.entry(fr.round() as u32).or_insert(f32::MIN);
+            *entry = entry.max(*val as f32);
+        });
+    map
+}
+
+/// Writes the `(x, y)` pairs as CSV with the header `x,y`.
+fn write_csv<W: Write>(writer: &mut W, data: &[(f64, f64)]) -> std::io::Result<()> {
+    writeln!(writer, "x,y")?;
+    for (x, y) in data {
+        writeln!(writer, "{},{}", x, y)?;
+    }
+    Ok(())
+}
+
+/// Returns the index of the `(frequency, magnitude)` pair with the highest magnitude.
+/// DC (0 Hz) is ignored, as it is often the highest value but never the pitch.
+fn peak_index(data: &[(f64, f64)]) -> Option<usize> {
+    data.iter()
+        .enumerate()
+        .filter(|(_, (fr, _))| *fr > 0.0)
+        .max_by(|(_, (_, m1)), (_, (_, m2))| m1.total_cmp(m2))
+        .map(|(i, _)| i)
+}
+
+/// Returns the frequency of the peak of the `(frequency, magnitude)` pairs (see
+/// [`peak_index`]), refined by quadratic interpolation of the magnitudes of the peak and
+/// its two neighbours. This is more precise than the resolution of the spectrum, as needed
+/// for [`WindowConfig::show_tuner`].
+fn interpolated_peak_frequency(data: &[(f64, f64)]) -> Option<f64> {
+    let i = peak_index(data)?;
+    if i == 0 || i + 1 == data.len() {
+        return Some(data[i].0);
+    }
+    let ((f_prev, a), (f, b), (f_next, c)) = (data[i - 1], data[i], data[i + 1]);
+    let denominator = a - 2.0 * b + c;
+    if denominator == 0.0 {
+        return Some(f);
+    }
+    // offset of the vertex of the parabola in bins, in [-0.5; 0.5]
+    let offset = 0.5 * (a - c) / denominator;
+    Some(f + offset * (f_next - f_prev) / 2.0)
+}
+
+/// Formats the nearest note of the frequency and the deviation from it, e.g. "A4 +12¢".
+fn tuner_label(frequency: f64) -> String {
+    let note = nearest_note(frequency as f32);
+    format!("{} {:+}¢", note, note.cents.round() as i32)
+}
+
+/// Labels the peak frequency of the spectrum and/or the tuner readout in the upper left
+/// corner of the chart. See [`WindowConfig::show_peak_freq`] and [`WindowConfig::show_tuner`].
+fn draw_peak_frequency(
+    chart: &mut ChartContext<BitMapBackend<BGRXPixel>, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    data: &[(f64, f64)],
+    window_cfg: &WindowConfig,
+) {
+    if let Some(peak) = interpolated_peak_frequency(data) {
+        let mut label = Vec::new();
+        if window_cfg.show_peak_freq {
+            label.push(format!("Peak: {:.0} Hz", peak));
+        }
+        if window_cfg.show_tuner {
+            label.push(tuner_label(peak));
+        }
+        draw_corner_label(chart, label.join("  "), window_cfg);
+    }
+}
+
+/// Draws the text into the upper left corner of the chart.
+fn draw_corner_label(
+    chart: &mut ChartContext<BitMapBackend<BGRXPixel>, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    text: String,
+    window_cfg: &WindowConfig,
+) {
+    let (x_range, y_range) = (chart.x_range(), chart.y_range());
+    chart
+        .draw_series(std::iter::once(Text::new(
+            text,
+            (x_range.start, y_range.end),
+            (window_cfg.font(), 20)
+                .into_font()
+                .color(&window_cfg.foreground()),
+        )))
+        .unwrap();
+}
+
+/// Draws labeled horizontal guide lines `(y, label)` over the whole width of the chart.
+fn draw_guide_lines(
+    chart: &mut ChartContext<BitMapBackend<BGRXPixel>, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    guide_lines: &[(f64, String)],
+    font: &str,
+) {
+    let x_range = chart.x_range();
+    for (y, label) in guide_lines {
+        chart
+            .draw_series(LineSeries::new(
+                vec![(x_range.start, *y), (x_range.end, *y)],
+                GUIDE_LINE_COLOR,
+            ))
+            .unwrap();
+        chart
+            .draw_series(std::iter::once(Text::new(
+                label.clone(),
+                (x_range.start, *y),
+                (font, 15).into_font().color(&GUIDE_LINE_COLOR),
+            )))
+            .unwrap();
+    }
+}
+
 /// Fills the given chart with the waveform over time, from the past (left) to now/realtime (right).
 fn fill_chart_complex_fnc(
-    mut chart: ChartContext<BitMapBackend<BGRXPixel>, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    chart: &mut ChartContext<BitMapBackend<BGRXPixel>, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
     audio_data: Vec<(f64, f64)>,
+    color: RGBColor,
+    label: Option<&str>,
 ) {
     // dedicated function; otherwise lifetime problems/compiler errors
+    let series = chart
+        .draw_series(LineSeries::new(audio_data, &color))
+        .unwrap();
+    if let Some(label) = label {
+        add_legend_entry(series, label, color);
+    }
+}
+
+/// Calls the transform function (or all functions of a [`TransformFn::Overlay`]) and
+/// appends the resulting series to `series`.
+#[allow(clippy::too_many_arguments)]
+fn apply_transform_fn<'a>(
+    transform_fn: &TransformFn<'a>,
+    audio_data: &[f32],
+    sample_rate: f32,
+    frequency_limit: FrequencyLimit,
+    color: RGBColor,
+    label: Option<&'a str>,
+    series: &mut Vec<Series<'a>>,
+) {
+    let output = match transform_fn {
+        TransformFn::Basic(fnc) => TransformOutput::Waveform(
+            fnc(audio_data, sample_rate),
+            audio_data.len() as f64 / sample_rate as f64,
+        ),
+        TransformFn::BasicWithSpan(fnc) => {
+            let (data, span_s) = fnc(audio_data, sample_rate);
+            TransformOutput::Waveform(data, span_s)
+        }
+        TransformFn::Complex(fnc) => TransformOutput::Pairs(fnc(audio_data, sample_rate)),
+        TransformFn::Spectrum => {
+            TransformOutput::Pairs(live_spectrum(audio_data, sample_rate, frequency_limit))
+        }
+        TransformFn::Overlay(fncs) => {
+            for (fnc, color, label) in fncs {
+                apply_transform_fn(
+                    fnc,
+                    audio_data,
+                    sample_rate,
+                    frequency_limit,
+                    *color,
+                    Some(label),
+                    series,
+                );
+            }
+            return;
+        }
+    };
+    series.push(Series {
+        output,
+        color,
+        label,
+    });
+}
+
+/// Adds the series with a short line in its color to the legend.
+fn add_legend_entry(
+    series: &mut SeriesAnno<BitMapBackend<BGRXPixel>>,
+    label: &str,
+    color: RGBColor,
+) {
+    series
+        .label(label)
+        .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+}
+
+/// Draws the legend with the labels of all series into the upper right corner.
+fn draw_legend<'a>(
+    chart: &mut ChartContext<
+        'a,
+        BitMapBackend<'a, BGRXPixel>,
+        Cartesian2d<RangedCoordf64, RangedCoordf64>,
+    >,
+    window_cfg: &WindowConfig,
+) {
+    let foreground = window_cfg.foreground();
     chart
-        .draw_series(LineSeries::new(audio_data, &CYAN))
+        .configure_series_labels()
+        .background_style(window_cfg.background)
+        .border_style(foreground)
+        .label_font((window_cfg.font(), 15).into_font().color(&foreground))
+        .draw()
         .unwrap();
 }
 
 /// Fills the given chart with the waveform over time, from the past (left) to now/realtime (right).
+/// The values are spread evenly over `x_span` (in the unit of the x-axis), ending at zero.
 fn fill_chart_waveform_over_time(
-    mut chart: ChartContext<BitMapBackend<BGRXPixel>, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    chart: &mut ChartContext<BitMapBackend<BGRXPixel>, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
     audio_data: &[f32],
-    time_per_sample: f64,
+    x_span: f64,
     audio_history_buf_len: usize,
+    color: RGBColor,
+    label: Option<&str>,
 ) {
-    debug_assert_eq!(audio_data.len(), audio_history_buf_len);
-    let timeshift = audio_history_buf_len as f64 * time_per_sample;
+    let x_per_sample = x_span / audio_data.len().max(1) as f64;
+    let visible_range = chart.x_range();
+    let visible_values =
+        audio_data.len() as f64 * ((visible_range.end - visible_range.start) / x_span).min(1.0);
+    // Skip less elements, if only a part of the data is visible (zoomed in) or if there are
+    // less elements than samples in the history (e.g. decimated data).
+    let step = (4.0 * visible_values / audio_history_buf_len as f64).round() as usize;
+    let step = step.max(1);
 
     // calculate timestamp of each index (x coordinate)
     let data_iter = audio_data
@@ -229,24 +1011,326 @@ fn fill_chart_waveform_over_time(
         //
         // If we skip too many elements, animation becomes un-smooth.... 4 seems to be sensible
         // due to tests by me.
-        .filter(|(i, _)| *i % 4 == 0)
+        .filter(|(i, _)| *i % step == 0)
         .map(|(i, amplitude)| {
-            let timestamp = time_per_sample * (i as f64) - timeshift;
+            let timestamp = x_per_sample * (i as f64) - x_span;
             // Values for amplitude in interval [-1.0; 1.0]
             (timestamp, (*amplitude) as f64)
-        });
+        })
+        // only draw what is visible
+        .filter(|(timestamp, _)| visible_range.contains(timestamp));
 
     // Draws all points as a line of connected points.
     // LineSeries is reasonable efficient for the big workload, but still very expensive..
     // (4-6ms in release mode on my intel i5 10th generation)
-    chart
-        .draw_series(LineSeries::new(data_iter, &CYAN))
+    let series = chart
+        .draw_series(LineSeries::new(data_iter, &color))
         .unwrap();
+    if let Some(label) = label {
+        add_legend_entry(series, label, color);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tests::testutil::TEST_OUT_DIR;
+
+    #[test]
+    fn test_spectrum_x_range() {
+        assert_eq!(spectrum_x_range(FrequencyLimit::All, 44100.0), 0.0..22050.0);
+        assert_eq!(
+            spectrum_x_range(FrequencyLimit::Range(20.0, 5000.0), 44100.0),
+            20.0..5000.0
+        );
+        assert_eq!(
+            spectrum_x_range(FrequencyLimit::Min(20.0), 48000.0),
+            20.0..24000.0
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_spectrum_x_range_above_nyquist() {
+        spectrum_x_range(FrequencyLimit::Max(30000.0), 44100.0);
+    }
+
+    #[test]
+    fn test_live_spectrum_respects_frequency_limit() {
+        let audio_data = (0..44100)
+            .map(|i| i as f32 / 44100.0)
+            .map(|t| (t * 1000.0 * 2.0 * std::f32::consts::PI).sin())
+            .collect::<Vec<_>>();
+        let spectrum = live_spectrum(&audio_data, 44100.0, FrequencyLimit::Range(20.0, 5000.0));
+        assert!(spectrum.iter().all(|(fr, _)| (20.0..=5000.0).contains(fr)));
+        let (peak_frequency, _) = spectrum
+            .iter()
+            .max_by(|(_, m1), (_, m2)| m1.partial_cmp(m2).unwrap())
+            .unwrap();
+        assert!((peak_frequency - 1000.0).abs() < 25.0);
+    }
+
+    #[test]
+    fn test_audio_buffer_len_of_window_config() {
+        let window_cfg = WindowConfig {
+            history_s: 1.0,
+            ..WindowConfig::default()
+        };
+        assert_eq!(window_cfg.audio_buffer_len(44100), 65536);
+        assert!((window_cfg.audio_buffer_duration_s(44100) - 1.486).abs() < 0.001);
+        assert_eq!(window_cfg.audio_buffer_duration_s(32768), 1.0);
+    }
+
+    #[test]
+    fn test_ringbuffer_len() {
+        // previous fixed history of 5 seconds
+        assert_eq!(ringbuffer_len(44100, 5.0), 262144);
+        assert_eq!(ringbuffer_len(44100, 1.0), 65536);
+        assert_eq!(ringbuffer_len(48000, 0.5), 32768);
+        assert_eq!(
+            init_ringbuffer(ringbuffer_len(44100, 1.0))
+                .lock()
+                .unwrap()
+                .len(),
+            65536
+        );
+    }
+
+    #[test]
+    fn test_apply_transform_fn_overlay() {
+        use plotters::style::RED;
+        let transform_fn = TransformFn::Overlay(vec![
+            (
+                TransformFn::Basic(|vals, _| vals.to_vec()),
+                CYAN,
+                "original",
+            ),
+            (
+                TransformFn::Basic(|vals, _| vals.iter().map(|val| val * 0.5).collect()),
+                RED,
+                "halved",
+            ),
+        ]);
+        assert!(!transform_fn.has_spectrum());
+        let mut series = Vec::new();
+        apply_transform_fn(
+            &transform_fn,
+            &[1.0, -1.0],
+            44100.0,
+            FrequencyLimit::All,
+            CYAN,
+            None,
+            &mut series,
+        );
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[1].color, RED);
+        assert_eq!(series[1].label, Some("halved"));
+        assert!(
+            matches!(&series[1].output, TransformOutput::Waveform(data, span_s) if data == &[0.5, -0.5] && *span_s == 2.0 / 44100.0)
+        );
+        assert!(
+            TransformFn::Overlay(vec![(TransformFn::Spectrum, RED, "spectrum")]).has_spectrum()
+        );
+    }
+
+    #[test]
+    fn test_apply_transform_fn_basic_with_span() {
+        // keeps every second sample of the latest 4 samples
+        let transform_fn = TransformFn::BasicWithSpan(|vals, sampling_rate| {
+            let latest = latest_n(vals, 4);
+            (
+                latest.iter().step_by(2).copied().collect(),
+                latest.len() as f64 / sampling_rate as f64,
+            )
+        });
+        assert!(!transform_fn.has_pairs());
+        let mut series = Vec::new();
+        apply_transform_fn(
+            &transform_fn,
+            &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0],
+            4.0,
+            FrequencyLimit::All,
+            CYAN,
+            None,
+            &mut series,
+        );
+        assert!(
+            matches!(&series[0].output, TransformOutput::Waveform(data, span_s) if data == &[5.0, 7.0] && *span_s == 1.0)
+        );
+    }
+
+    /// Transforms audio data of the [`DummyInput`] like the render loop, but without window.
+    #[test]
+    fn test_transform_pipeline_with_dummy_input() {
+        use crate::dynamic::dummy_input::DummyInput;
+        let latest_audio_data = Arc::new(Mutex::new(AllocRingBuffer::new(8192)));
+        let input = DummyInput::sine(latest_audio_data.clone(), 44100, 1000.0, 0.5);
+        let begin = Instant::now();
+        while latest_audio_data.lock().unwrap().len() < LIVE_FFT_SIZE {
+            assert!(begin.elapsed() < Duration::from_secs(10), "no audio data");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        drop(input);
+
+        let audio_data = latest_audio_data.lock().unwrap().to_vec();
+        let mut series = Vec::new();
+        apply_transform_fn(
+            &TransformFn::Spectrum,
+            &audio_data,
+            44100.0,
+            FrequencyLimit::All,
+            CYAN,
+            None,
+            &mut series,
+        );
+        let peak = match &series[0].output {
+            TransformOutput::Pairs(data) => interpolated_peak_frequency(data).unwrap(),
+            TransformOutput::Waveform(..) => panic!("spectrum expected"),
+        };
+        assert!((peak - 1000.0).abs() < 25.0, "{}", peak);
+    }
+
+    #[test]
+    fn test_peak_frequency_ignores_dc() {
+        let data = [(0.0, 10.0), (21.5, 0.5), (43.0, 2.0), (64.5, 1.0)];
+        assert_eq!(peak_index(&data), Some(2));
+        assert_eq!(peak_index(&[(0.0, 1.0)]), None);
+
+        let audio_data = (0..4096)
+            .map(|i| i as f32 / 44100.0)
+            .map(|t| 0.5 + (t * 440.0 * 2.0 * std::f32::consts::PI).sin())
+            .collect::<Vec<_>>();
+        let spectrum = live_spectrum(&audio_data, 44100.0, FrequencyLimit::All);
+        let peak = interpolated_peak_frequency(&spectrum).unwrap();
+        assert!((peak - 440.0).abs() < 25.0);
+    }
+
+    #[test]
+    fn test_interpolated_peak_frequency() {
+        // symmetric neighbours: the peak is exactly at the bin
+        let data = [(0.0, 10.0), (10.0, 1.0), (20.0, 2.0), (30.0, 1.0)];
+        assert_eq!(interpolated_peak_frequency(&data), Some(20.0));
+        // the louder upper neighbour moves the peak towards it
+        let data = [(0.0, 10.0), (10.0, 1.0), (20.0, 2.0), (30.0, 1.5)];
+        let peak = interpolated_peak_frequency(&data).unwrap();
+        assert!(peak > 20.0 && peak < 25.0, "{}", peak);
+        assert_eq!(interpolated_peak_frequency(&[(0.0, 1.0)]), None);
+
+        // A4 detuned by +12 cents; the resolution of the spectrum is ~10.8 Hz
+        let frequency = 440.0 * 2_f32.powf(12.0 / 1200.0);
+        let audio_data = (0..4096)
+            .map(|i| i as f32 / 44100.0)
+            .map(|t| (t * frequency * 2.0 * std::f32::consts::PI).sin())
+            .collect::<Vec<_>>();
+        let spectrum = live_spectrum(&audio_data, 44100.0, FrequencyLimit::All);
+        let peak = interpolated_peak_frequency(&spectrum).unwrap();
+        assert!((peak - frequency as f64).abs() < 1.0, "{}", peak);
+        assert_eq!(tuner_label(peak).split(' ').next(), Some("A4"));
+    }
+
+    #[test]
+    fn test_tuner_label() {
+        assert_eq!(tuner_label(440.0), "A4 +0¢");
+        assert_eq!(tuner_label(443.0), "A4 +12¢");
+        assert_eq!(tuner_label(430.0), "A4 -40¢");
+        assert_eq!(tuner_label(261.63), "C4 +0¢");
+    }
+
+    #[test]
+    fn test_to_spectrum_map() {
+        let map = to_spectrum_map(&[(-21.5, 1.0), (0.0, 0.5), (21.4, 0.25), (21.6, 0.75)]);
+        assert_eq!(
+            map.into_iter().collect::<Vec<_>>(),
+            vec![(0, 0.5), (21, 0.25), (22, 0.75)]
+        );
+        let map = to_spectrum_map(&[(10.2, 0.25), (9.8, 0.75)]);
+        assert_eq!(map.into_iter().collect::<Vec<_>>(), vec![(10, 0.75)]);
+    }
+
+    #[test]
+    fn test_export_spectrum_png() {
+        let data = live_spectrum(&[0.0; 4096], 44100.0, FrequencyLimit::All);
+        let directory = Path::new(TEST_OUT_DIR).join("export_spectrum_png");
+        // only the files of this run are counted
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+        export_spectrum_png(&directory, &data);
+        let pngs = std::fs::read_dir(&directory).unwrap().count();
+        assert_eq!(pngs, 1);
+    }
+
+    #[test]
+    fn test_export_spectrum_png_invalid_frame() {
+        let directory = Path::new(TEST_OUT_DIR).join("export_spectrum_png_invalid_frame");
+        // only the files of this run are counted
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+        // neither panics nor writes a file
+        export_spectrum_png(&directory, &[(100.0, 1.0)]);
+        export_spectrum_png(&directory, &[(100.0, 1.0), (200.0, f64::NAN)]);
+        let pngs = std::fs::read_dir(&directory).unwrap().count();
+        assert_eq!(pngs, 0);
+    }
+
+    #[test]
+    fn test_export_waveform_png() {
+        let audio_data = (0..4410)
+            .map(|i| (i as f32 * 0.1).sin())
+            .collect::<Vec<_>>();
+        let directory = Path::new(TEST_OUT_DIR).join("export_waveform_png");
+        // only the files of this run are counted
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+        export_waveform_png(&directory, &audio_data);
+        let pngs = std::fs::read_dir(&directory).unwrap().count();
+        assert_eq!(pngs, 1);
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let mut csv = Vec::new();
+        write_csv(&mut csv, &[(0.0, 1.5), (21.5, 0.25)]).unwrap();
+        assert_eq!(String::from_utf8(csv).unwrap(), "x,y\n0,1.5\n21.5,0.25\n");
+    }
+
+    #[test]
+    fn test_time_axis_view_stays_within_history() {
+        let mut view = TimeAxisView::new(5.0);
+        assert_eq!(view.x_range(), -5.0..0.0);
+
+        // can't pan or zoom out without zooming in first
+        view.apply_key(Key::Left);
+        view.apply_key(Key::Minus);
+        assert_eq!(view.x_range(), -5.0..0.0);
+
+        view.apply_key(Key::Equal);
+        view.apply_key(Key::Equal);
+        assert_eq!(view.x_range(), -3.2..0.0);
+        view.apply_key(Key::Right);
+        assert_eq!(view.x_range(), -3.2..0.0);
+        (0..100).for_each(|_| view.apply_key(Key::Left));
+        let range = view.x_range();
+        assert!((range.start - -5.0).abs() < 1e-9);
+        assert!((range.end - -1.8).abs() < 1e-9);
+
+        view.apply_key(Key::R);
+        assert_eq!(view.x_range(), -5.0..0.0);
+    }
+
+    #[test]
+    fn test_find_trigger_shift() {
+        let audio_data = [0.5, -0.5, 0.5, -0.5, -0.5, 0.5, 0.5, 0.5];
+        // latest rising edge at or before index 6 is at index 5
+        assert_eq!(find_trigger_shift(&audio_data, 0.0, 6), Some(1));
+        assert_eq!(find_trigger_shift(&audio_data, 0.0, 4), Some(2));
+        assert_eq!(find_trigger_shift(&audio_data, 0.0, 1), None);
+        assert_eq!(find_trigger_shift(&audio_data, 0.6, 7), None);
+
+        let shifted = shift_right(&audio_data, 1);
+        assert_eq!(shifted, vec![0.0, 0.5, -0.5, 0.5, -0.5, -0.5, 0.5, 0.5]);
+        // trigger point is at the left border now
+        assert!(shifted[5] < 0.0 && shifted[6] >= 0.0);
+    }
 
     #[ignore]
     #[test]
@@ -261,6 +1345,7 @@ mod tests {
             "y-axis",
             AudioDevAndCfg::new(None, None),
             TransformFn::Basic(|vals, _| vals.to_vec()),
+            WindowConfig::default(),
         );
     }
 }
@@ -0,0 +1,136 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Optional timing measurements of the phases of the render loop of
+//! [`super::open_window_connect_audio`]. See [`super::visualize_minifb::WindowConfig::collect_timing`].
+
+use std::time::Duration;
+
+/// Minimum, average, and maximum duration of one phase of the render loop.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct PhaseTiming {
+    pub min: Duration,
+    pub avg: Duration,
+    pub max: Duration,
+}
+
+/// Summary of the timing of all frames of the render loop. Helps to find out whether
+/// the transform function or the drawing is the bottleneck.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct FrameTimingSummary {
+    /// Number of rendered frames.
+    pub frames: u32,
+    /// Time spent in the transform function ([`super::TransformFn`]).
+    pub transform: PhaseTiming,
+    /// Time spent drawing the charts (without the transform function).
+    pub drawing: PhaseTiming,
+    /// Time spent updating the window. This includes the sleep time of the
+    /// refresh rate limit.
+    pub update: PhaseTiming,
+}
+
+/// Collects the durations of one phase without storing every single measurement.
+#[derive(Debug, Copy, Clone, Default)]
+struct PhaseTimingCollector {
+    min: Option<Duration>,
+    max: Duration,
+    sum: Duration,
+    count: u32,
+}
+
+impl PhaseTimingCollector {
+    fn add(&mut self, duration: Duration) {
+        self.min = Some(self.min.map_or(duration, |min| min.min(duration)));
+        self.max = self.max.max(duration);
+        self.sum += duration;
+        self.count += 1;
+    }
+
+    fn summary(&self) -> PhaseTiming {
+        PhaseTiming {
+            min: self.min.unwrap_or_default(),
+            avg: self.sum.checked_div(self.count).unwrap_or_default(),
+            max: self.max,
+        }
+    }
+}
+
+/// Collects the timing of all frames of the render loop.
+#[derive(Debug, Copy, Clone, Default)]
+pub(crate) struct FrameTimingCollector {
+    transform: PhaseTimingCollector,
+    drawing: PhaseTimingCollector,
+    update: PhaseTimingCollector,
+}
+
+impl FrameTimingCollector {
+    /// Adds the measurements of one frame.
+    pub(crate) fn add_frame(&mut self, transform: Duration, drawing: Duration, update: Duration) {
+        self.transform.add(transform);
+        self.drawing.add(drawing);
+        self.update.add(update);
+    }
+
+    pub(crate) fn summary(&self) -> FrameTimingSummary {
+        FrameTimingSummary {
+            frames: self.transform.count,
+            transform: self.transform.summary(),
+            drawing: self.drawing.summary(),
+            update: self.update.summary(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_timing_summary() {
+        let mut collector = FrameTimingCollector::default();
+        assert_eq!(collector.summary(), FrameTimingSummary::default());
+
+        let ms = Duration::from_millis;
+        collector.add_frame(ms(2), ms(5), ms(7));
+        collector.add_frame(ms(4), ms(3), ms(7));
+        let summary = collector.summary();
+        assert_eq!(summary.frames, 2);
+        assert_eq!(
+            summary.transform,
+            PhaseTiming {
+                min: ms(2),
+                avg: ms(3),
+                max: ms(4)
+            }
+        );
+        assert_eq!(
+            summary.drawing,
+            PhaseTiming {
+                min: ms(3),
+                avg: ms(4),
+                max: ms(5)
+            }
+        );
+        assert_eq!(summary.update.avg, ms(7));
+    }
+}
@@ -24,22 +24,386 @@ SOFTWARE.
 //! Helps to visualize audio data
 
 use crate::dynamic::window_top_btm::pixel_buf::PixelBuf;
-use minifb::{Window, WindowOptions};
+use crate::util::DEFAULT_FONT;
+use crate::ChannelGain;
+use minifb::{Key, Window, WindowOptions};
 use plotters::chart::{ChartBuilder, ChartState};
 use plotters::coord::cartesian::Cartesian2d;
 use plotters::coord::types::RangedCoordf64;
 use plotters::coord::Shift;
 use plotters::drawing::{DrawingArea, IntoDrawingArea};
-use plotters::style::{IntoFont, WHITE};
+use plotters::style::{IntoFont, RGBColor, BLACK, WHITE};
 use plotters_bitmap::bitmap_pixel::BGRXPixel;
 use plotters_bitmap::BitMapBackend;
+use spectrum_analyzer::FrequencyLimit;
 use std::borrow::{Borrow, BorrowMut};
 use std::ops::Range;
+use std::path::PathBuf;
+use std::time::Duration;
 
 /// Width of the window.
 pub const DEFAULT_W: usize = 1280;
 /// Height of the window.
 pub const DEFAULT_H: usize = 720;
+/// Lower end of a logarithmic x-axis, if the linear x-axis starts at or below zero.
+const LOG_X_MIN: f64 = 20.0;
+/// Range of a y-axis in dB below its upper end, if the linear y-axis starts at or below zero.
+const DB_SPAN: f64 = 100.0;
+
+/// Trigger of the upper chart (original audio data), like on an oscilloscope.
+/// See [`WindowConfig::trigger`].
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub enum TriggerMode {
+    /// The upper chart shows the scrolling audio history.
+    #[default]
+    Off,
+    /// The upper chart is aligned to the latest rising edge through `level`, so that the
+    /// left border of the visible time range always shows the trigger point. This results
+    /// in a stationary trace for repetitive waveforms. If there is no rising edge, the
+    /// history scrolls as usual.
+    RisingEdge {
+        /// Amplitude that the signal has to cross upwards, e.g. 0.0.
+        level: f32,
+    },
+}
+
+/// Unit of the time axis of the upper chart. See [`WindowConfig::x_axis_unit`].
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum XAxisUnit {
+    /// Time in seconds relative to the latest sample.
+    #[default]
+    Seconds,
+    /// Number of samples relative to the latest sample. Useful to reason about exact
+    /// sample offsets.
+    Samples,
+}
+
+impl XAxisUnit {
+    /// Distance of two consecutive samples on the time axis.
+    pub(crate) const fn x_per_sample(self, time_per_sample: f64) -> f64 {
+        match self {
+            Self::Seconds => time_per_sample,
+            Self::Samples => 1.0,
+        }
+    }
+
+    /// Description of the time axis.
+    const fn description(self) -> &'static str {
+        match self {
+            Self::Seconds => "time (seconds)",
+            Self::Samples => "time (samples)",
+        }
+    }
+}
+
+/// Scales of the axes of the lower chart for transform functions with `(x, y)` pairs,
+/// e.g. a spectrum. See [`WindowConfig::btm_scale`].
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct BtmScale {
+    /// Logarithmic x-axis, e.g. to see the low frequencies of a spectrum in detail. The
+    /// x-axis starts at 20, if the linear x-axis starts at or below zero.
+    pub log_x: bool,
+    /// y-axis in dB (`20 * log10(y)`), e.g. to see quiet partials next to loud ones. The
+    /// y-axis spans 100 dB, if the linear y-axis starts at or below zero.
+    pub db: bool,
+}
+
+impl BtmScale {
+    /// Toggles the scales: `L` toggles [`Self::log_x`], `D` toggles [`Self::db`].
+    // const fn with mutable references requires a newer Rust version than the MSRV
+    #[allow(clippy::missing_const_for_fn)]
+    pub(crate) fn apply_key(&mut self, key: Key) {
+        match key {
+            Key::L => self.log_x = !self.log_x,
+            Key::D => self.db = !self.db,
+            _ => {}
+        }
+    }
+
+    /// Converts the linear x-range into the x-range of the chart. Returns `None`, if the
+    /// x-range ends at or below the start of a logarithmic x-axis.
+    pub(crate) fn x_range(self, x_range: Range<f64>) -> Option<Range<f64>> {
+        if !self.log_x {
+            return Some(x_range);
+        }
+        let start = if x_range.start > 0.0 {
+            x_range.start
+        } else {
+            LOG_X_MIN
+        };
+        if x_range.end <= start {
+            return None;
+        }
+        Some(start.log10()..x_range.end.log10())
+    }
+
+    /// Converts the linear y-range into the y-range of the chart. Returns `None`, if the
+    /// y-range ends at or below zero, e.g. for data that is already in dB.
+    pub(crate) fn y_range(self, y_range: Range<f64>) -> Option<Range<f64>> {
+        if !self.db {
+            return Some(y_range);
+        }
+        if y_range.end <= 0.0 {
+            return None;
+        }
+        let end = to_db(y_range.end);
+        let start = if y_range.start > 0.0 {
+            to_db(y_range.start)
+        } else {
+            end - DB_SPAN
+        };
+        Some(start..end)
+    }
+
+    /// Whether the scales can be applied to the linear ranges of the lower chart. See
+    /// [`Self::x_range`] and [`Self::y_range`].
+    pub(crate) fn is_applicable(self, x_range: &Range<f64>, y_range: &Range<f64>) -> bool {
+        self.x_range(x_range.clone()).is_some() && self.y_range(y_range.clone()).is_some()
+    }
+
+    /// Converts a linear `(x, y)` pair into the coordinates of the chart. Values below
+    /// `y_min` (e.g. zero in dB) are raised to it. Returns `None` for pairs that have no
+    /// position on a logarithmic x-axis.
+    pub(crate) fn scale_point(self, (x, y): (f64, f64), y_min: f64) -> Option<(f64, f64)> {
+        let x = if self.log_x {
+            if x <= 0.0 {
+                return None;
+            }
+            x.log10()
+        } else {
+            x
+        };
+        let y = if self.db { to_db(y).max(y_min) } else { y };
+        Some((x, y))
+    }
+}
+
+/// Converts an amplitude into dB. Zero results in negative infinity.
+fn to_db(value: f64) -> f64 {
+    20.0 * value.log10()
+}
+
+/// Icon of the GUI window. See [`WindowConfig::icon`]. [`minifb`] supports icons only on
+/// some platforms; variants of other platforms are ignored with a warning.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WindowIcon {
+    /// Path of an `.ico` file. Only supported on Windows.
+    Path(String),
+    /// Pixels in the format of the X11 property `_NET_WM_ICON`, i.e. the width and the
+    /// height followed by `width * height` ARGB pixels. Only supported on Linux with X11.
+    Argb(Vec<u64>),
+}
+
+impl WindowIcon {
+    /// Sets the icon of the window, if the platform supports it.
+    fn apply(&self, window: &mut Window) {
+        match self {
+            #[cfg(target_os = "windows")]
+            Self::Path(path) => {
+                use std::os::windows::ffi::OsStrExt;
+                // minifb expects a nul-terminated wide string that outlives `set_icon`
+                let wide = std::ffi::OsStr::new(path)
+                    .encode_wide()
+                    .chain(core::iter::once(0))
+                    .collect::<Vec<u16>>();
+                window.set_icon(minifb::Icon::Path(wide.as_ptr()));
+            }
+            #[cfg(target_os = "linux")]
+            Self::Argb(argb) => {
+                use std::convert::TryFrom;
+                match minifb::Icon::try_from(argb.as_slice()) {
+                    Ok(icon) => window.set_icon(icon),
+                    Err(e) => log::warn!("invalid window icon: {:?}", e),
+                }
+            }
+            #[allow(unreachable_patterns)]
+            _ => {
+                let _ = window;
+                log::warn!("window icon {:?} is not supported on this platform", self);
+            }
+        }
+    }
+}
+
+/// Additional configuration of the GUI window and its charts.
+/// Use [`WindowConfig::default`] and override the fields you need.
+#[derive(Debug, Clone)]
+pub struct WindowConfig {
+    /// Number of labels on the x-axis of each chart. Wide windows benefit from more labels,
+    /// narrow windows from less. Default is 10.
+    pub x_labels: usize,
+    /// Number of labels on the y-axis of each chart. Default is 10.
+    pub y_labels: usize,
+    /// Measure how long the phases of each frame take (transform function, drawing,
+    /// window update). The summary is returned when the window is closed. Default is false.
+    pub collect_timing: bool,
+    /// Headroom of the y-axis of the upper chart (original audio data) beyond the full scale
+    /// amplitude, i.e. the y-axis ranges from `-(1.0 + y_headroom)` to `1.0 + y_headroom`.
+    /// A small headroom keeps samples at exactly +-1.0 off the border of the chart. Increase
+    /// it, if the audio data can exceed +-1.0. Default is 0.01.
+    pub y_headroom: f64,
+    /// Options of the [`minifb`] window, e.g. `topmost` or `borderless`.
+    /// Default is [`WindowOptions::default`].
+    pub window_options: WindowOptions,
+    /// Initial position `(x, y)` of the window on the desktop, e.g. to open it on a
+    /// secondary display. If `None`, the platform decides. Default is `None`.
+    pub position: Option<(isize, isize)>,
+    /// Trigger of the upper chart. Default is [`TriggerMode::Off`]. Zoom into the upper
+    /// chart to see a few periods of the waveform.
+    pub trigger: TriggerMode,
+    /// Labeled horizontal guide lines `(y, label)` in the lower chart, e.g. reference levels
+    /// like `(-20.0, "-20 dB")` for a spectrum in dB. They are drawn each frame below the
+    /// data and are independent of the mesh. Default is no guide lines.
+    pub btm_guide_lines: Vec<(f64, String)>,
+    /// Gain and mute state per input channel, applied before the audio data is downmixed
+    /// and visualized. Channels without an entry keep their level. Default is empty.
+    pub channel_gains: Vec<ChannelGain>,
+    /// Directory of all exported files: the CSV file of `C`, the spectrum PNG of `P` and the
+    /// waveform PNG of [`Self::run_for`]. See [`super::open_window_connect_audio`]. Default is
+    /// the current working directory.
+    pub export_dir: PathBuf,
+    /// Frequency range of [`super::TransformFn::Spectrum`]. It also determines the x-axis of
+    /// the lower chart, so that the analyzed and the displayed range always match.
+    /// Default is [`FrequencyLimit::All`].
+    pub frequency_limit: FrequencyLimit,
+    /// Background color of the window. The labels and axes are drawn in white on dark
+    /// backgrounds and in black on light backgrounds. Default is [`BLACK`].
+    pub background: RGBColor,
+    /// Duration of the audio history in seconds, i.e. the range of the upper chart and the
+    /// amount of audio data passed to the transform function. It bounds the memory of the
+    /// audio buffer, e.g. for embedded devices.
+    ///
+    /// The buffer holds `history_s * sampling_rate` samples rounded up to the next power of
+    /// two, e.g. 65536 `f32` (256 KiB) for 1 second at 44.1 kHz. Default is 5.0, which
+    /// results in 262144 `f32` (1 MiB) at 44.1 kHz. See [`Self::audio_buffer_len`] and
+    /// [`Self::audio_buffer_duration_s`] for the actual values.
+    pub history_s: f64,
+    /// Unit of the time axis of the upper chart. It also applies to the lower chart of
+    /// [`super::TransformFn::Basic`]. Default is [`XAxisUnit::Seconds`].
+    pub x_axis_unit: XAxisUnit,
+    /// Labels the dominant frequency (the highest magnitude, ignoring DC) of the lower chart
+    /// in its upper left corner each frame, e.g. "Peak: 440 Hz". Applies to
+    /// [`super::TransformFn::Spectrum`] and to [`super::TransformFn::Complex`] with a
+    /// spectrum. Useful as rough pitch display. Default is false.
+    pub show_peak_freq: bool,
+    /// Shows the nearest note in equal temperament of the dominant frequency of the lower
+    /// chart and its deviation in cents, e.g. "A4 +12¢", like a chromatic tuner. It is
+    /// drawn next to [`Self::show_peak_freq`], if both are enabled. Default is false.
+    pub show_tuner: bool,
+    /// Shows the momentary loudness of the audio input in the upper left corner of the upper
+    /// chart, approximating LUFS. See `dynamic::loudness`. Requires the `filters` feature;
+    /// without it, this has no effect. Default is false.
+    pub show_loudness: bool,
+    /// Initial scales of the lower chart for [`super::TransformFn::Complex`],
+    /// [`super::TransformFn::Spectrum`] and overlays of them. `L` and `D` toggle them while
+    /// the window is open. Default is linear.
+    pub btm_scale: BtmScale,
+    /// Font family of the labels, e.g. `"DejaVu Sans"`, if [`DEFAULT_FONT`] isn't installed.
+    /// If `None`, [`DEFAULT_FONT`] is used. The family must be installed on the system; no
+    /// font is bundled and there is no fallback, i.e. the labels stay blank.
+    pub font: Option<String>,
+    /// Fraction of the window height that the upper chart takes, in `(0.0; 1.0)`. The lower
+    /// chart gets the rest, e.g. 0.3 gives the lower chart 70% of the height. Default is 0.5.
+    pub split_ratio: f64,
+    /// Closes the window automatically after the given duration, e.g. to generate demo
+    /// assets from scripts. Before it closes, the audio recorded in this time (at most
+    /// [`Self::history_s`]) is written as waveform PNG to a timestamped file in
+    /// [`Self::export_dir`]. If it isn't an existing directory, an error is logged and the
+    /// window doesn't open. Default is `None`, i.e. the window stays open.
+    pub run_for: Option<Duration>,
+    /// Draws the legend with the label of each series of [`super::TransformFn::Overlay`]
+    /// into the lower chart. Drawing it costs time each frame; disable it, if the colors
+    /// are self-explanatory. Default is true.
+    pub show_legend: bool,
+    /// Text of the title bar. If `None`, the `name` of the window is used.
+    pub title: Option<String>,
+    /// Icon of the window. If `None`, the platform default is used. Default is `None`.
+    pub icon: Option<WindowIcon>,
+}
+
+impl WindowConfig {
+    /// Range of the y-axis of the upper chart (original audio data).
+    pub(crate) fn y_range_top(&self) -> Range<f64> {
+        assert!(self.y_headroom >= 0.0, "y_headroom must not be negative!");
+        let max = 1.0 + self.y_headroom;
+        -max..max
+    }
+
+    /// Number of samples of the audio buffer of the window for the given sampling rate,
+    /// i.e. [`Self::history_s`] rounded up to the next power of two samples. The upper chart
+    /// spans this number of samples.
+    pub fn audio_buffer_len(&self, sampling_rate: u32) -> usize {
+        super::ringbuffer_len(sampling_rate as usize, self.history_s)
+    }
+
+    /// Actual duration in seconds of the audio buffer of the window for the given sampling
+    /// rate. It is at least [`Self::history_s`]. See [`Self::audio_buffer_len`].
+    pub fn audio_buffer_duration_s(&self, sampling_rate: u32) -> f64 {
+        self.audio_buffer_len(sampling_rate) as f64 / sampling_rate as f64
+    }
+
+    /// Height of the upper chart in pixels. See [`Self::split_ratio`].
+    fn split_height(&self, height: usize) -> usize {
+        assert!(
+            self.split_ratio > 0.0 && self.split_ratio < 1.0,
+            "split_ratio must be in (0.0; 1.0)!"
+        );
+        (height as f64 * self.split_ratio).round() as usize
+    }
+
+    /// Text of the title bar of the window with the given name. See [`Self::title`].
+    pub(crate) fn title<'a>(&'a self, name: &'a str) -> &'a str {
+        self.title.as_deref().unwrap_or(name)
+    }
+
+    /// Font family of the labels. See [`Self::font`].
+    pub(crate) fn font(&self) -> &str {
+        self.font.as_deref().unwrap_or(DEFAULT_FONT)
+    }
+
+    /// Color of the labels and axes; contrasts with [`Self::background`].
+    pub(crate) fn foreground(&self) -> RGBColor {
+        let RGBColor(r, g, b) = self.background;
+        // relative luminance (ITU-R BT.601)
+        let luminance = 0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64;
+        if luminance > 127.5 {
+            BLACK
+        } else {
+            WHITE
+        }
+    }
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            x_labels: 10,
+            y_labels: 10,
+            collect_timing: false,
+            y_headroom: 0.01,
+            window_options: WindowOptions::default(),
+            position: None,
+            trigger: TriggerMode::Off,
+            btm_guide_lines: Vec::new(),
+            channel_gains: Vec::new(),
+            export_dir: PathBuf::from("."),
+            frequency_limit: FrequencyLimit::All,
+            background: BLACK,
+            history_s: 5.0,
+            x_axis_unit: XAxisUnit::Seconds,
+            show_peak_freq: false,
+            show_tuner: false,
+            show_loudness: false,
+            btm_scale: BtmScale::default(),
+            font: None,
+            split_ratio: 0.5,
+            run_for: None,
+            show_legend: true,
+            title: None,
+            icon: None,
+        }
+    }
+}
 
 /// Initializes the [`minifb`] window and draws the initial grid into it.
 /// It splits the drawing area into an upper chart and a lower chart. The
@@ -57,7 +421,9 @@ pub const DEFAULT_H: usize = 720;
 /// - `x_desc` Description for the x-axis of the lower (=custom) diagram.
 /// - `y_desc` Description for the y-axis of the lower (=custom) diagram.
 /// - `audio_buffer_len` Number of elements in the audio buffer. Needed for the scaling of the x-axis.
-/// - `time_per_sample` Time per sample. Needed for the scaling of the x-axis.
+/// - `time_per_sample` Time per sample. Needed for the scaling of the x-axis, if
+///   [`WindowConfig::x_axis_unit`] is [`XAxisUnit::Seconds`].
+/// - `window_cfg` See [`WindowConfig`].
 ///
 /// # Returns
 /// - window object
@@ -75,44 +441,96 @@ pub fn setup_window(
     y_desc: &str,
     audio_buffer_len: usize,
     time_per_sample: f64,
+    window_cfg: &WindowConfig,
 ) -> (
     Window,
     ChartState<Cartesian2d<RangedCoordf64, RangedCoordf64>>,
     ChartState<Cartesian2d<RangedCoordf64, RangedCoordf64>>,
     PixelBuf,
+) {
+    let (window, mut top_charts, btm_chart, pixel_buf) = setup_window_stacked(
+        name,
+        preferred_height,
+        preferred_width,
+        preferred_x_range,
+        preferred_y_range,
+        x_desc,
+        y_desc,
+        audio_buffer_len,
+        time_per_sample,
+        1,
+        window_cfg,
+    );
+    (window, top_charts.remove(0), btm_chart, pixel_buf)
+}
+
+/// Like [`setup_window`] but with `top_count` upper charts stacked on top of each other,
+/// e.g. one per input device. See [`get_drawing_areas_stacked`].
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub(crate) fn setup_window_stacked(
+    name: &str,
+    preferred_height: Option<usize>,
+    preferred_width: Option<usize>,
+    preferred_x_range: Option<Range<f64>>,
+    preferred_y_range: Option<Range<f64>>,
+    x_desc: &str,
+    y_desc: &str,
+    audio_buffer_len: usize,
+    time_per_sample: f64,
+    top_count: usize,
+    window_cfg: &WindowConfig,
+) -> (
+    Window,
+    Vec<ChartState<Cartesian2d<RangedCoordf64, RangedCoordf64>>>,
+    ChartState<Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    PixelBuf,
 ) {
     let height = preferred_height.unwrap_or(DEFAULT_H);
     let width = preferred_width.unwrap_or(DEFAULT_W);
-    let mut window =
-        Window::new(&String::from(name), width, height, WindowOptions::default()).unwrap();
-    let x_range_top = -(audio_buffer_len as f64 * time_per_sample)..0.0;
-    let y_range_top = -1.0..1.01;
+    let mut window = Window::new(
+        window_cfg.title(name),
+        width,
+        height,
+        window_cfg.window_options,
+    )
+    .unwrap();
+    if let Some((x, y)) = window_cfg.position {
+        window.set_position(x, y);
+    }
+    if let Some(icon) = &window_cfg.icon {
+        icon.apply(&mut window);
+    }
+    let x_per_sample = window_cfg.x_axis_unit.x_per_sample(time_per_sample);
+    let x_range_top = -(audio_buffer_len as f64 * x_per_sample)..0.0;
+    let y_range_top = window_cfg.y_range_top();
     let x_range_btm = preferred_x_range.unwrap_or_else(|| x_range_top.clone());
     let y_range_btm = preferred_y_range.unwrap_or_else(|| y_range_top.clone());
 
     // Buffer where we draw the Chart as bitmap into: we update the "minifb" window from it too
     let mut pixel_buf = PixelBuf(vec![0_u32; width * height]);
 
-    let (top_drawing_area, btm_drawing_area) =
-        get_drawing_areas(pixel_buf.borrow_mut(), width, height);
+    let (top_drawing_areas, btm_drawing_area) =
+        get_drawing_areas_stacked(pixel_buf.borrow_mut(), width, height, top_count, window_cfg);
 
-    let top_chart = draw_chart(
-        top_drawing_area,
-        x_range_top,
-        y_range_top,
-        "time (seconds)",
-        "amplitude",
+    let top_charts = top_drawing_areas
+        .into_iter()
+        .map(|area| draw_top_chart(area, x_range_top.clone(), window_cfg))
+        .collect();
+    let btm_chart = draw_chart(
+        btm_drawing_area,
+        x_range_btm,
+        y_range_btm,
+        x_desc,
+        y_desc,
+        false,
+        window_cfg,
     );
-    let btm_chart = draw_chart(btm_drawing_area, x_range_btm, y_range_btm, x_desc, y_desc);
-
-    // unborrow "pixel_buf" again
-    //drop(root_drawing_area);
 
     window
         .update_with_buffer(pixel_buf.borrow(), width, height)
         .unwrap();
 
-    (window, top_chart, btm_chart, pixel_buf)
+    (window, top_charts, btm_chart, pixel_buf)
 }
 
 /// Returns two drawing areas, that together fill the whole window.
@@ -126,6 +544,43 @@ pub fn get_drawing_areas(
     DrawingArea<BitMapBackend<BGRXPixel>, Shift>,
     DrawingArea<BitMapBackend<BGRXPixel>, Shift>,
 ) {
+    get_drawing_areas_with_config(pixel_buf, width, height, &WindowConfig::default())
+}
+
+/// Like [`get_drawing_areas`] but the window is split according to
+/// [`WindowConfig::split_ratio`].
+pub fn get_drawing_areas_with_config<'a>(
+    pixel_buf: &'a mut [u8],
+    width: usize,
+    height: usize,
+    window_cfg: &WindowConfig,
+) -> (
+    DrawingArea<BitMapBackend<'a, BGRXPixel>, Shift>,
+    DrawingArea<BitMapBackend<'a, BGRXPixel>, Shift>,
+) {
+    let (mut top_drawing_areas, btm_drawing_area) =
+        get_drawing_areas_stacked(pixel_buf, width, height, 1, window_cfg);
+    (top_drawing_areas.remove(0), btm_drawing_area)
+}
+
+/// Like [`get_drawing_areas_with_config`] but the upper part of the window is split evenly
+/// into `top_count` drawing areas, e.g. one per input device. The lower drawing area keeps
+/// its size.
+#[allow(clippy::type_complexity)]
+pub fn get_drawing_areas_stacked<'a>(
+    pixel_buf: &'a mut [u8],
+    width: usize,
+    height: usize,
+    top_count: usize,
+    window_cfg: &WindowConfig,
+) -> (
+    Vec<DrawingArea<BitMapBackend<'a, BGRXPixel>, Shift>>,
+    DrawingArea<BitMapBackend<'a, BGRXPixel>, Shift>,
+) {
+    assert!(
+        top_count > 0,
+        "there must be at least one upper drawing area!"
+    );
     // BGRXPixel format required by "minifb" (alpha, red, green, blue)
     let root_drawing_area = BitMapBackend::<BGRXPixel>::with_buffer_and_format(
         pixel_buf.borrow_mut(),
@@ -135,8 +590,68 @@ pub fn get_drawing_areas(
     .into_drawing_area();
 
     let (top_drawing_area, btm_drawing_area) =
-        root_drawing_area.split_vertically((height / 2) as f64);
-    (top_drawing_area, btm_drawing_area)
+        root_drawing_area.split_vertically(window_cfg.split_height(height) as f64);
+    (
+        top_drawing_area.split_evenly((top_count, 1)),
+        btm_drawing_area,
+    )
+}
+
+/// Draws the upper chart (original audio data) with the given x-range into the
+/// drawing area. Used for the initial drawing and whenever the visible time range
+/// of the upper chart changes (zoom and pan).
+pub(crate) fn draw_top_chart(
+    drawing_area: DrawingArea<BitMapBackend<BGRXPixel>, Shift>,
+    x_range: Range<f64>,
+    window_cfg: &WindowConfig,
+) -> ChartState<Cartesian2d<RangedCoordf64, RangedCoordf64>> {
+    draw_chart(
+        drawing_area,
+        x_range,
+        window_cfg.y_range_top(),
+        window_cfg.x_axis_unit.description(),
+        "amplitude",
+        false,
+        window_cfg,
+    )
+}
+
+/// Draws the lower chart with the given scales into the drawing area. The ranges are
+/// linear; they are converted according to `scale`, which must be applicable to them (see
+/// [`BtmScale::is_applicable`]). Used whenever the scales change.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn draw_btm_chart(
+    drawing_area: DrawingArea<BitMapBackend<BGRXPixel>, Shift>,
+    x_range: Range<f64>,
+    y_range: Range<f64>,
+    x_desc: &str,
+    y_desc: &str,
+    scale: BtmScale,
+    window_cfg: &WindowConfig,
+) -> ChartState<Cartesian2d<RangedCoordf64, RangedCoordf64>> {
+    let y_desc = if scale.db {
+        format!("{} (dB)", y_desc)
+    } else {
+        y_desc.to_string()
+    };
+    draw_chart(
+        drawing_area,
+        scale
+            .x_range(x_range)
+            .expect("the scale must be applicable to the x-range"),
+        scale
+            .y_range(y_range)
+            .expect("the scale must be applicable to the y-range"),
+        x_desc,
+        &y_desc,
+        scale.log_x,
+        window_cfg,
+    )
+}
+
+/// Labels a logarithmic x-axis with the linear values.
+fn format_log_x_label(x: &f64) -> String {
+    format!("{:.0}", 10_f64.powf(*x))
 }
 
 /// Draws the initial, empty into the dedicated drawing area.
@@ -153,7 +668,10 @@ fn draw_chart<'a>(
     y_range: Range<f64>,
     x_desc: &'a str,
     y_desc: &'a str,
+    log_x: bool,
+    window_cfg: &WindowConfig,
 ) -> ChartState<Cartesian2d<RangedCoordf64, RangedCoordf64>> {
+    drawing_area.fill(&window_cfg.background).unwrap();
     let mut chart = ChartBuilder::on(&drawing_area)
         // margin effects the distance to the border of the window of the chart
         .margin(10)
@@ -161,16 +679,22 @@ fn draw_chart<'a>(
         .build_cartesian_2d(x_range, y_range)
         .unwrap();
 
-    chart
-        .configure_mesh()
-        .label_style(("sans-serif", 15).into_font().color(&WHITE))
-        .x_desc(x_desc)
-        .y_desc(y_desc)
-        .x_labels(10)
-        .y_labels(10)
-        .axis_style(WHITE)
-        .draw()
-        .unwrap();
+    let mut mesh = chart.configure_mesh();
+    mesh.label_style(
+        (window_cfg.font(), 15)
+            .into_font()
+            .color(&window_cfg.foreground()),
+    )
+    .x_desc(x_desc)
+    .y_desc(y_desc)
+    .x_labels(window_cfg.x_labels)
+    .y_labels(window_cfg.y_labels)
+    .axis_style(window_cfg.foreground());
+    // the x-axis shows log10(x); the labels show x
+    if log_x {
+        mesh.x_label_formatter(&format_log_x_label);
+    }
+    mesh.draw().unwrap();
 
     chart.into_chart_state()
 }
@@ -193,10 +717,152 @@ mod tests {
             "y-axis",
             (44100 * 5_usize).next_power_of_two(),
             1.0 / 44100.0,
+            &super::WindowConfig::default(),
         );
         while window.is_open() && !window.is_key_down(Key::Escape) {
             // REQUIRED to get keyboard and mouse events (such as close)
             window.update();
         }
     }
+
+    #[test]
+    fn test_y_range_top_is_symmetric() {
+        let window_cfg = super::WindowConfig {
+            y_headroom: 0.5,
+            ..super::WindowConfig::default()
+        };
+        assert_eq!(window_cfg.y_range_top(), -1.5..1.5);
+    }
+
+    #[test]
+    fn test_foreground_contrasts_with_background() {
+        use plotters::style::{RGBColor, BLACK, WHITE};
+        for (background, foreground) in [
+            (BLACK, WHITE),
+            (WHITE, BLACK),
+            (RGBColor(255, 220, 0), BLACK),
+            (RGBColor(0, 0, 128), WHITE),
+        ] {
+            let window_cfg = super::WindowConfig {
+                background,
+                ..super::WindowConfig::default()
+            };
+            assert_eq!(window_cfg.foreground(), foreground);
+        }
+    }
+
+    #[test]
+    fn test_split_height() {
+        let window_cfg = super::WindowConfig {
+            split_ratio: 0.3,
+            ..super::WindowConfig::default()
+        };
+        assert_eq!(window_cfg.split_height(720), 216);
+        assert_eq!(super::WindowConfig::default().split_height(720), 360);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_split_height_invalid_ratio() {
+        let window_cfg = super::WindowConfig {
+            split_ratio: 1.0,
+            ..super::WindowConfig::default()
+        };
+        window_cfg.split_height(720);
+    }
+
+    #[test]
+    fn test_title() {
+        let mut window_cfg = super::WindowConfig::default();
+        assert_eq!(window_cfg.title("name"), "name");
+        window_cfg.title = Some(String::from("My Tool"));
+        assert_eq!(window_cfg.title("name"), "My Tool");
+    }
+
+    #[test]
+    fn test_get_drawing_areas_stacked() {
+        let mut pixel_buf = vec![0_u8; 400 * 300 * 4];
+        let (top_drawing_areas, btm_drawing_area) = super::get_drawing_areas_stacked(
+            &mut pixel_buf,
+            400,
+            300,
+            3,
+            &super::WindowConfig::default(),
+        );
+        assert_eq!(top_drawing_areas.len(), 3);
+        top_drawing_areas
+            .iter()
+            .for_each(|area| assert_eq!(area.dim_in_pixel(), (400, 50)));
+        assert_eq!(btm_drawing_area.dim_in_pixel(), (400, 150));
+    }
+
+    #[test]
+    fn test_x_axis_unit() {
+        use super::XAxisUnit;
+        assert_eq!(
+            XAxisUnit::Seconds.x_per_sample(1.0 / 44100.0),
+            1.0 / 44100.0
+        );
+        assert_eq!(XAxisUnit::Samples.x_per_sample(1.0 / 44100.0), 1.0);
+        assert_eq!(XAxisUnit::Samples.description(), "time (samples)");
+    }
+
+    #[test]
+    fn test_btm_scale() {
+        use super::BtmScale;
+        let mut scale = BtmScale::default();
+        assert_eq!(scale.x_range(0.0..20000.0), Some(0.0..20000.0));
+        assert_eq!(scale.scale_point((100.0, 0.5), 0.0), Some((100.0, 0.5)));
+
+        scale.apply_key(Key::L);
+        scale.apply_key(Key::D);
+        assert_eq!(
+            scale,
+            BtmScale {
+                log_x: true,
+                db: true
+            }
+        );
+        assert_eq!(
+            scale.x_range(0.0..20000.0),
+            Some(20_f64.log10()..20000_f64.log10())
+        );
+        assert_eq!(scale.x_range(10.0..1000.0), Some(1.0..3.0));
+        assert_eq!(scale.y_range(0.0..1.0), Some(-100.0..0.0));
+        assert_eq!(scale.y_range(0.01..10.0), Some(-40.0..20.0));
+        assert_eq!(scale.scale_point((100.0, 0.1), -100.0), Some((2.0, -20.0)));
+        // silence is raised to the lower end of the y-axis; DC has no position
+        assert_eq!(scale.scale_point((100.0, 0.0), -100.0), Some((2.0, -100.0)));
+        assert_eq!(scale.scale_point((0.0, 0.1), -100.0), None);
+
+        scale.apply_key(Key::L);
+        assert_eq!(
+            scale,
+            BtmScale {
+                log_x: false,
+                db: true
+            }
+        );
+    }
+
+    #[test]
+    fn test_btm_scale_not_applicable() {
+        use super::BtmScale;
+        let db = BtmScale {
+            log_x: false,
+            db: true,
+        };
+        // e.g. a spectrum that is already in dB
+        assert_eq!(db.y_range(-120.0..0.0), None);
+        assert!(!db.is_applicable(&(0.0..20000.0), &(-120.0..0.0)));
+        assert!(db.is_applicable(&(0.0..20000.0), &(0.0..1.0)));
+
+        let log_x = BtmScale {
+            log_x: true,
+            db: false,
+        };
+        assert_eq!(log_x.x_range(-5.0..0.0), None);
+        assert_eq!(log_x.x_range(0.0..20.0), None);
+        assert!(!log_x.is_applicable(&(-5.0..0.0), &(-1.0..1.0)));
+    }
 }
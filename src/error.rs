@@ -0,0 +1,93 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+//! Common error type of the fallible visualization functions, such as
+//! [`crate::waveform::png_file::try_waveform_static_png_visualize`].
+
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Error of a static visualization. Unifies all errors that can occur, so that the
+/// caller has to handle only one error type.
+#[derive(Debug)]
+pub enum VizError {
+    /// The output file can't be created or written.
+    Io(std::io::Error),
+    /// The image can't be encoded, e.g. due to invalid dimensions.
+    Encoding(png::EncodingError),
+    /// There is no audio data to visualize.
+    EmptyInput,
+    /// The length of the interleaved audio data is not a multiple of the number of
+    /// channels, e.g. an odd length for stereo.
+    InvalidLength {
+        /// Number of samples.
+        len: usize,
+        /// Number of channels.
+        channel_count: usize,
+    },
+    /// The configuration is invalid, e.g. a non-positive full-scale amplitude. Contains a
+    /// description of the problem.
+    InvalidConfig(&'static str),
+}
+
+impl Display for VizError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "I/O error: {}", e),
+            Self::Encoding(e) => write!(f, "Can't encode the image: {}", e),
+            Self::EmptyInput => write!(f, "There is no audio data!"),
+            Self::InvalidLength { len, channel_count } => write!(
+                f,
+                "The length of the audio data ({}) is not a multiple of the number of channels ({})",
+                len, channel_count
+            ),
+            Self::InvalidConfig(reason) => write!(f, "Invalid configuration: {}", reason),
+        }
+    }
+}
+
+impl Error for VizError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Encoding(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for VizError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<png::EncodingError> for VizError {
+    fn from(e: png::EncodingError) -> Self {
+        match e {
+            png::EncodingError::IoError(e) => Self::Io(e),
+            e => Self::Encoding(e),
+        }
+    }
+}
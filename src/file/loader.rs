@@ -0,0 +1,265 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Loads audio files from disk with the [`symphonia`] crate. See [`load_audio_file`].
+
+use std::fs::File;
+use std::io::ErrorKind;
+use std::path::Path;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::DecoderOptions;
+use symphonia::core::errors::Error;
+use symphonia::core::formats::{FormatOptions, FormatReader};
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// Decoded audio data of an audio file. See [`load_audio_file`].
+#[derive(Debug, Clone)]
+pub struct AudioFile {
+    /// Interleaved samples (LRLR for stereo) at full 32 bit resolution.
+    samples: Vec<i32>,
+    /// Sampling rate, e.g. 44100.
+    sample_rate: u32,
+    /// Number of channels.
+    channel_count: usize,
+}
+
+impl AudioFile {
+    /// Getter for the interleaved samples (LRLR for stereo). Samples of audio files with a
+    /// lower resolution are scaled up, i.e. [`i32::MAX`] is always the full scale amplitude.
+    pub fn samples(&self) -> &[i32] {
+        &self.samples
+    }
+
+    /// Getter for the sampling rate.
+    pub const fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Getter for the number of channels.
+    pub const fn channel_count(&self) -> usize {
+        self.channel_count
+    }
+
+    /// Returns the audio data as mono in interval `[-1.0; 1.0]`. The channels of
+    /// multichannel audio are averaged.
+    pub fn to_mono_f32(&self) -> Vec<f32> {
+        self.samples
+            .chunks_exact(self.channel_count)
+            .map(|frame| {
+                let sum = frame.iter().map(|x| *x as f64).sum::<f64>();
+                (sum / self.channel_count as f64 / i32::MAX as f64) as f32
+            })
+            .collect()
+    }
+}
+
+/// An audio track of an audio file, e.g. one of several tracks of a MKV file. See
+/// [`list_tracks`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackInfo {
+    /// Index of the track in the file; see [`load_audio_file_track`].
+    index: usize,
+    /// Short name of the codec, e.g. `"mp3"`.
+    codec: String,
+    /// Sampling rate, if it is known before decoding.
+    sample_rate: Option<u32>,
+    /// Number of channels, if it is known before decoding.
+    channel_count: Option<usize>,
+}
+
+impl TrackInfo {
+    /// Getter for the index of the track in the file.
+    pub const fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Getter for the short name of the codec, e.g. `"mp3"`. It is `"unknown"`, if
+    /// [`symphonia`] has no decoder for the codec.
+    pub fn codec(&self) -> &str {
+        &self.codec
+    }
+
+    /// Getter for the sampling rate, if it is known before decoding.
+    pub const fn sample_rate(&self) -> Option<u32> {
+        self.sample_rate
+    }
+
+    /// Getter for the number of channels, if it is known before decoding.
+    pub const fn channel_count(&self) -> Option<usize> {
+        self.channel_count
+    }
+}
+
+/// Lists the tracks of the audio file at the given path, e.g. to choose the track for
+/// [`load_audio_file_track`]. Most audio files have one track; containers like MKV or
+/// OGG can have several.
+pub fn list_tracks(path: &Path) -> Result<Vec<TrackInfo>, Error> {
+    let format = open_format(path)?;
+    let tracks = format
+        .tracks()
+        .iter()
+        .enumerate()
+        .map(|(index, track)| TrackInfo {
+            index,
+            codec: symphonia::default::get_codecs()
+                .get_codec(track.codec_params.codec)
+                .map_or("unknown", |codec| codec.short_name)
+                .to_string(),
+            sample_rate: track.codec_params.sample_rate,
+            channel_count: track.codec_params.channels.map(|x| x.count()),
+        })
+        .collect();
+    Ok(tracks)
+}
+
+/// Loads and decodes the audio file at the given path with [`symphonia`]. Supports all
+/// formats that are enabled in the default features of [`symphonia`] plus MP3.
+///
+/// The format is guessed from the file extension and the content of the file.
+/// The first audio track of the file is used. See [`load_audio_file_track`] for files
+/// with several tracks.
+pub fn load_audio_file(path: &Path) -> Result<AudioFile, Error> {
+    load_audio_file_track(path, 0)
+}
+
+/// Like [`load_audio_file`] but decodes the track with the given index, e.g. the second
+/// audio track of a MKV file. See [`list_tracks`]. Returns an error, if the track doesn't
+/// exist.
+pub fn load_audio_file_track(path: &Path, track_index: usize) -> Result<AudioFile, Error> {
+    let mut format = open_format(path)?;
+
+    let track = match format.tracks().get(track_index) {
+        Some(track) => track,
+        None if format.tracks().is_empty() => {
+            return Err(Error::Unsupported("file contains no tracks"))
+        }
+        None => return Err(Error::Unsupported("track doesn't exist")),
+    };
+    let track_id = track.id;
+    let mut sample_rate = track.codec_params.sample_rate.unwrap_or(0);
+    let mut channel_count = track.codec_params.channels.map(|x| x.count()).unwrap_or(0);
+    let mut decoder =
+        symphonia::default::get_codecs().make(&track.codec_params, &DecoderOptions::default())?;
+
+    let mut samples = vec![];
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            // symphonia signals the end of the stream this way
+            Err(Error::IoError(e)) if e.kind() == ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // corrupt packets can be skipped
+            Err(Error::DecodeError(_)) => continue,
+            Err(e) => return Err(e),
+        };
+        let spec = *decoded.spec();
+        sample_rate = spec.rate;
+        channel_count = spec.channels.count();
+
+        let mut sample_buf = SampleBuffer::<i32>::new(decoded.capacity() as u64, spec);
+        sample_buf.copy_interleaved_ref(decoded);
+        samples.extend_from_slice(sample_buf.samples());
+    }
+
+    if channel_count == 0 {
+        return Err(Error::Unsupported("unknown number of channels"));
+    }
+
+    Ok(AudioFile {
+        samples,
+        sample_rate,
+        channel_count,
+    })
+}
+
+/// Opens the audio file at the given path and probes its format. The format is guessed from
+/// the file extension and the content of the file.
+fn open_format(path: &Path) -> Result<Box<dyn FormatReader>, Error> {
+    let file = File::open(path)?;
+    let media_source_stream = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|x| x.to_str()) {
+        hint.with_extension(extension);
+    }
+
+    let probed = symphonia::default::get_probe().format(
+        &hint,
+        media_source_stream,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    )?;
+    Ok(probed.format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::testutil::TEST_SAMPLES_DIR;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_load_mp3() {
+        let mut path = PathBuf::new();
+        path.push(TEST_SAMPLES_DIR);
+        path.push("sample_1.mp3");
+
+        let audio = load_audio_file(&path).unwrap();
+        assert_eq!(audio.channel_count(), 2);
+        assert_eq!(audio.sample_rate(), 44100);
+        assert!(!audio.samples().is_empty());
+        assert_eq!(audio.to_mono_f32().len(), audio.samples().len() / 2);
+    }
+
+    #[test]
+    fn test_list_tracks_and_load_track() {
+        let mut path = PathBuf::new();
+        path.push(TEST_SAMPLES_DIR);
+        path.push("sample_1.mp3");
+
+        let tracks = list_tracks(&path).unwrap();
+        assert_eq!(tracks.len(), 1);
+        assert_eq!(tracks[0].index(), 0);
+        assert_eq!(tracks[0].codec(), "mp3");
+        assert_eq!(tracks[0].sample_rate(), Some(44100));
+        assert_eq!(tracks[0].channel_count(), Some(2));
+
+        let audio = load_audio_file_track(&path, 0).unwrap();
+        assert_eq!(audio.samples(), load_audio_file(&path).unwrap().samples());
+        assert!(load_audio_file_track(&path, 1).is_err());
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        assert!(load_audio_file(Path::new("does/not/exist.mp3")).is_err());
+    }
+}
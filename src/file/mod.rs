@@ -0,0 +1,88 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! High-level "batteries included" functionality that works on audio files.
+//!
+//! It combines the audio file loader, the spectrum calculation, and the static
+//! visualizations of this crate. See [`visualize_file`].
+
+pub mod loader;
+
+use crate::file::loader::load_audio_file;
+use crate::spectrum::fft::samples_to_spectrum;
+use crate::spectrum::plotters_png_file::spectrum_static_plotters_png_visualize;
+use crate::waveform::png_file::waveform_static_png_visualize_i32;
+use crate::{ChannelInterleavement, Channels};
+use std::path::{Path, PathBuf};
+use symphonia::core::errors::Error;
+
+/// Number of samples per FFT used by [`visualize_file`].
+const FFT_SIZE: usize = 4096;
+
+/// Loads the given audio file and writes a waveform PNG and a spectrum PNG into `out_dir`.
+///
+/// The file names are derived from the name of the audio file, e.g.
+/// `song_waveform.png` and `song_spectrum.png`. Like
+/// [`waveform_static_png_visualize_i32`], stereo audio results in two waveform files and
+/// audio with more than two channels in one waveform file per channel.
+///
+/// The spectrum is the average spectrum of the whole file.
+///
+/// Returns the paths of all written files (waveform files first, spectrum file last)
+/// or an error, if the audio file can't be loaded or decoded.
+pub fn visualize_file(path: &str, out_dir: &str) -> Result<Vec<PathBuf>, Error> {
+    let path = Path::new(path);
+    let audio = load_audio_file(path)?;
+    let name = path.file_stem().and_then(|x| x.to_str()).unwrap_or("audio");
+
+    let waveform_filename = format!("{}_waveform.png", name);
+    let mut paths = waveform_static_png_visualize_i32(
+        audio.samples(),
+        i32::MAX,
+        Channels::from_count(audio.channel_count(), ChannelInterleavement::LRLR),
+        out_dir,
+        &waveform_filename,
+    );
+
+    let spectrum = samples_to_spectrum(&audio.to_mono_f32(), audio.sample_rate(), FFT_SIZE);
+    let spectrum_filename = format!("{}_spectrum.png", name);
+    spectrum_static_plotters_png_visualize(&spectrum, out_dir, &spectrum_filename);
+    paths.push(Path::new(out_dir).join(spectrum_filename));
+
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::testutil::{TEST_OUT_DIR, TEST_SAMPLES_DIR};
+
+    #[test]
+    fn test_visualize_file() {
+        let paths =
+            visualize_file(&format!("{}/sample_1.mp3", TEST_SAMPLES_DIR), TEST_OUT_DIR).unwrap();
+        // stereo: left and right waveform + spectrum
+        assert_eq!(paths.len(), 3);
+        assert!(paths.iter().all(|path| path.exists()));
+    }
+}
@@ -0,0 +1,265 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Filters to visualize filtered audio data, e.g. in the lower chart of
+//! [`crate::dynamic::window_top_btm::open_window_connect_audio`].
+//!
+//! Requires the `filters` feature.
+
+use biquad::{Biquad, Coefficients, DirectForm1, ToHertz, Type};
+
+/// Q of a Butterworth filter (`1/sqrt(2)`), i.e. a maximally flat passband.
+pub const Q_BUTTERWORTH: f32 = biquad::Q_BUTTERWORTH_F32;
+
+/// Creates a biquad filter. Panics, if the frequency is not in `(0, sample_rate / 2]`, i.e.
+/// above the Nyquist frequency, or if `q` is negative.
+fn make_biquad(
+    filter_type: Type<f32>,
+    frequency_hz: f32,
+    q: f32,
+    sample_rate: f32,
+) -> DirectForm1<f32> {
+    assert!(
+        frequency_hz > 0.0 && frequency_hz <= sample_rate / 2.0,
+        "the frequency must be in range (0, sample_rate / 2]!"
+    );
+    assert!(q >= 0.0, "q must not be negative!");
+    let coefficients =
+        Coefficients::<f32>::from_params(filter_type, sample_rate.hz(), frequency_hz.hz(), q)
+            .unwrap();
+    DirectForm1::<f32>::new(coefficients)
+}
+
+/// Filters the samples and returns the filtered samples. The state of the filter is kept.
+fn process(filter: &mut DirectForm1<f32>, samples: &[f32]) -> Vec<f32> {
+    samples.iter().map(|sample| filter.run(*sample)).collect()
+}
+
+/// Second order lowpass filter (biquad).
+///
+/// The filter is stateful: consecutive calls of [`Self::process`] continue the signal of
+/// the previous call, so a stream can be filtered chunk by chunk without discontinuities.
+/// [`crate::dynamic::window_top_btm::TransformFn::Basic`] gets the whole audio history
+/// each frame and not just the new samples. There, create a new filter per frame:
+/// `TransformFn::Basic(|samples, sr| BiquadLowpass::new(80.0, Q_BUTTERWORTH, sr).process(samples))`.
+#[derive(Debug, Copy, Clone)]
+pub struct BiquadLowpass {
+    filter: DirectForm1<f32>,
+}
+
+impl BiquadLowpass {
+    /// Creates a new lowpass filter. Panics, if the cutoff frequency is not in
+    /// `(0, sample_rate / 2]`, i.e. above the Nyquist frequency, or if `q` is negative.
+    pub fn new(cutoff_hz: f32, q: f32, sample_rate: f32) -> Self {
+        Self {
+            filter: make_biquad(Type::LowPass, cutoff_hz, q, sample_rate),
+        }
+    }
+
+    /// Filters the samples and returns the filtered samples. The state of the filter is
+    /// kept for the next call.
+    pub fn process(&mut self, samples: &[f32]) -> Vec<f32> {
+        process(&mut self.filter, samples)
+    }
+
+    /// Resets the state of the filter, e.g. before a new, unrelated signal is filtered.
+    pub fn reset(&mut self) {
+        self.filter.reset_state();
+    }
+}
+
+/// Second order highpass filter (biquad). Stateful like [`BiquadLowpass`].
+#[derive(Debug, Copy, Clone)]
+pub struct BiquadHighpass {
+    filter: DirectForm1<f32>,
+}
+
+impl BiquadHighpass {
+    /// Creates a new highpass filter. Panics, if the cutoff frequency is not in
+    /// `(0, sample_rate / 2]`, i.e. above the Nyquist frequency, or if `q` is negative.
+    pub fn new(cutoff_hz: f32, q: f32, sample_rate: f32) -> Self {
+        Self {
+            filter: make_biquad(Type::HighPass, cutoff_hz, q, sample_rate),
+        }
+    }
+
+    /// See [`BiquadLowpass::process`].
+    pub fn process(&mut self, samples: &[f32]) -> Vec<f32> {
+        process(&mut self.filter, samples)
+    }
+
+    /// See [`BiquadLowpass::reset`].
+    pub fn reset(&mut self) {
+        self.filter.reset_state();
+    }
+}
+
+/// Second order bandpass filter (biquad). Stateful like [`BiquadLowpass`].
+#[derive(Debug, Copy, Clone)]
+pub struct BiquadBandpass {
+    filter: DirectForm1<f32>,
+}
+
+impl BiquadBandpass {
+    /// Creates a new bandpass filter around `center_hz`. A higher `q` results in a
+    /// narrower band. The gain at the center frequency is `q` (constant skirt gain), so
+    /// use `q = 1.0` to keep the level of the center frequency. Panics, if the center
+    /// frequency is not in `(0, sample_rate / 2]`, i.e. above the Nyquist frequency, or if
+    /// `q` is negative.
+    pub fn new(center_hz: f32, q: f32, sample_rate: f32) -> Self {
+        Self {
+            filter: make_biquad(Type::BandPass, center_hz, q, sample_rate),
+        }
+    }
+
+    /// See [`BiquadLowpass::process`].
+    pub fn process(&mut self, samples: &[f32]) -> Vec<f32> {
+        process(&mut self.filter, samples)
+    }
+
+    /// See [`BiquadLowpass::reset`].
+    pub fn reset(&mut self) {
+        self.filter.reset_state();
+    }
+}
+
+/// K-weighting filter of loudness measurements: a high shelf (+4 dB above ~1.7 kHz), which
+/// models the acoustic effect of the head, followed by a highpass (~38 Hz).
+///
+/// Both stages are designed for the given sampling rate with the parameters of
+/// ITU-R BS.1770, but with generic biquad formulas. The response approximates the filter
+/// of the standard, i.e. it is not suitable for certified measurements. Stateful like
+/// [`BiquadLowpass`].
+#[derive(Debug, Copy, Clone)]
+pub struct KWeighting {
+    shelf: DirectForm1<f32>,
+    highpass: DirectForm1<f32>,
+}
+
+impl KWeighting {
+    /// Creates a new K-weighting filter. Panics, if the sampling rate is below 3.4 kHz.
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            shelf: make_biquad(Type::HighShelf(4.0), 1681.97, 0.7072, sample_rate),
+            highpass: make_biquad(Type::HighPass, 38.14, 0.5003, sample_rate),
+        }
+    }
+
+    /// See [`BiquadLowpass::process`].
+    pub fn process(&mut self, samples: &[f32]) -> Vec<f32> {
+        samples
+            .iter()
+            .map(|sample| self.highpass.run(self.shelf.run(*sample)))
+            .collect()
+    }
+
+    /// See [`BiquadLowpass::reset`].
+    pub fn reset(&mut self) {
+        self.shelf.reset_state();
+        self.highpass.reset_state();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(frequency: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| i as f32 / 44100.0)
+            .map(|t| (t * frequency * 2.0 * std::f32::consts::PI).sin())
+            .collect()
+    }
+
+    fn peak(samples: &[f32]) -> f32 {
+        samples
+            .iter()
+            .fold(0.0, |max, sample| sample.abs().max(max))
+    }
+
+    #[test]
+    fn test_biquad_lowpass() {
+        let low = sine(50.0, 44100);
+        let high = sine(5000.0, 44100);
+        let mut lowpass = BiquadLowpass::new(500.0, Q_BUTTERWORTH, 44100.0);
+        // skip the transient response
+        assert!(peak(&lowpass.process(&low)[4410..]) > 0.95);
+        lowpass.reset();
+        assert!(peak(&lowpass.process(&high)[4410..]) < 0.02);
+    }
+
+    #[test]
+    fn test_biquad_lowpass_keeps_state() {
+        let samples = sine(50.0, 4410);
+        let whole = BiquadLowpass::new(500.0, Q_BUTTERWORTH, 44100.0).process(&samples);
+
+        let mut lowpass = BiquadLowpass::new(500.0, Q_BUTTERWORTH, 44100.0);
+        let (first, second) = samples.split_at(1000);
+        let mut chunked = lowpass.process(first);
+        chunked.extend(lowpass.process(second));
+        assert_eq!(whole, chunked);
+    }
+
+    #[test]
+    fn test_biquad_highpass() {
+        let low = sine(50.0, 44100);
+        let high = sine(5000.0, 44100);
+        let mut highpass = BiquadHighpass::new(500.0, Q_BUTTERWORTH, 44100.0);
+        assert!(peak(&highpass.process(&low)[4410..]) < 0.02);
+        highpass.reset();
+        assert!(peak(&highpass.process(&high)[4410..]) > 0.95);
+    }
+
+    #[test]
+    fn test_biquad_bandpass() {
+        let mut bandpass = BiquadBandpass::new(1000.0, 1.0, 44100.0);
+        assert!((peak(&bandpass.process(&sine(1000.0, 44100))[4410..]) - 1.0).abs() < 0.05);
+        bandpass.reset();
+        assert!(peak(&bandpass.process(&sine(50.0, 44100))[4410..]) < 0.1);
+        bandpass.reset();
+        assert!(peak(&bandpass.process(&sine(15000.0, 44100))[4410..]) < 0.1);
+    }
+
+    #[test]
+    fn test_k_weighting() {
+        let mut k_weighting = KWeighting::new(44100.0);
+        // approx. +0.7 dB at 1 kHz, +4 dB at high frequencies, attenuated low frequencies
+        let gain_1k = peak(&k_weighting.process(&sine(1000.0, 44100))[4410..]);
+        assert!((gain_1k - 1.08).abs() < 0.05);
+        k_weighting.reset();
+        assert!(peak(&k_weighting.process(&sine(8000.0, 44100))[4410..]) > 1.5);
+        k_weighting.reset();
+        assert!(peak(&k_weighting.process(&sine(20.0, 44100))[4410..]) < 0.5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_biquad_lowpass_cutoff_above_nyquist() {
+        let _ = BiquadLowpass::new(30000.0, Q_BUTTERWORTH, 44100.0);
+    }
+
+    #[test]
+    fn test_biquad_lowpass_cutoff_at_nyquist() {
+        let _ = BiquadLowpass::new(22050.0, Q_BUTTERWORTH, 44100.0);
+    }
+}
@@ -46,6 +46,11 @@ pub mod spectrum;
 pub mod waveform;
 
 pub mod dynamic;
+pub mod error;
+pub mod file;
+#[cfg(feature = "filters")]
+pub mod filters;
+pub mod overview;
 #[cfg(test)]
 mod tests;
 pub mod util;
@@ -70,7 +75,7 @@ impl ChannelInterleavement {
     }
     /// Transforms the interleaved data into two vectors.
     /// Returns a tuple. First/left value is left channel, second/right value is right channel.
-    pub fn to_channel_data(&self, interleaved_data: &[i16]) -> (Vec<i16>, Vec<i16>) {
+    pub fn to_channel_data<T: Copy>(&self, interleaved_data: &[T]) -> (Vec<T>, Vec<T>) {
         let mut left_data = vec![];
         let mut right_data = vec![];
 
@@ -96,6 +101,63 @@ impl ChannelInterleavement {
 
         (left_data, right_data)
     }
+
+    /// Interleaves the data of the left and the right channel. Inverse of
+    /// [`Self::to_channel_data`]. Both channels must have the same length.
+    pub fn from_channel_data<T: Copy>(&self, left: &[T], right: &[T]) -> Vec<T> {
+        assert_eq!(
+            left.len(),
+            right.len(),
+            "left and right channel must have the same length!"
+        );
+
+        if self.is_lrlr() {
+            left.iter()
+                .zip(right.iter())
+                .flat_map(|(l, r)| [*l, *r])
+                .collect()
+        } else {
+            left.iter().chain(right.iter()).copied().collect()
+        }
+    }
+
+    /// Generalization of [`Self::to_channel_data`] for an arbitrary number of channels.
+    ///
+    /// For [`Self::LRLR`], the samples of all channels alternate (frame by frame). For
+    /// [`Self::LLRR`], the data contains all samples of the first channel, then all
+    /// samples of the second channel, and so on. Returns one vector per channel.
+    pub fn to_multichannel_data<T: Copy>(
+        &self,
+        interleaved_data: &[T],
+        channel_count: usize,
+    ) -> Vec<Vec<T>> {
+        assert!(channel_count > 0, "there must be at least one channel!");
+        assert_eq!(
+            0,
+            interleaved_data.len() % channel_count,
+            "the length of the audio data must be a multiple of the number of channels!"
+        );
+
+        if interleaved_data.is_empty() {
+            vec![Vec::new(); channel_count]
+        } else if self.is_lrlr() {
+            (0..channel_count)
+                .map(|channel| {
+                    interleaved_data
+                        .iter()
+                        .skip(channel)
+                        .step_by(channel_count)
+                        .copied()
+                        .collect()
+                })
+                .collect()
+        } else {
+            interleaved_data
+                .chunks_exact(interleaved_data.len() / channel_count)
+                .map(|channel_data| channel_data.to_vec())
+                .collect()
+        }
+    }
 }
 
 /// Describes the number of channels of an audio stream.
@@ -103,9 +165,35 @@ impl ChannelInterleavement {
 pub enum Channels {
     Mono,
     Stereo(ChannelInterleavement),
+    /// More than two channels. The interleavement describes the order of the samples
+    /// like in [`ChannelInterleavement::to_multichannel_data`].
+    Multi(usize, ChannelInterleavement),
 }
 
 impl Channels {
+    /// Creates the variant that fits the channel count, e.g. if the channel count comes
+    /// from a decoder at runtime.
+    ///
+    /// Returns [`Self::Mono`] for 1, [`Self::Stereo`] for 2 and [`Self::Multi`] for more
+    /// channels. The interleavement is ignored for mono. Panics, if the count is zero.
+    pub fn from_count(count: usize, interleavement: ChannelInterleavement) -> Self {
+        match count {
+            0 => panic!("there must be at least one channel!"),
+            1 => Self::Mono,
+            2 => Self::Stereo(interleavement),
+            _ => Self::Multi(count, interleavement),
+        }
+    }
+
+    /// Returns the number of channels.
+    pub const fn count(&self) -> usize {
+        match self {
+            Self::Mono => 1,
+            Self::Stereo(_) => 2,
+            Self::Multi(count, _) => *count,
+        }
+    }
+
     pub const fn is_mono(&self) -> bool {
         matches!(self, Self::Mono)
     }
@@ -114,6 +202,10 @@ impl Channels {
         matches!(self, Self::Stereo(_))
     }
 
+    pub const fn is_multi(&self) -> bool {
+        matches!(self, Self::Multi(..))
+    }
+
     pub fn stereo_interleavement(&self) -> ChannelInterleavement {
         match self {
             Self::Stereo(interleavmement) => *interleavmement,
@@ -121,3 +213,41 @@ impl Channels {
         }
     }
 }
+
+/// Gain and mute state of a single audio channel. It is applied to the samples of the
+/// channel before they are rendered.
+///
+/// Useful to focus on a single channel of multichannel audio, e.g. surround stems.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ChannelGain {
+    /// Linear factor for the samples of the channel. `1.0` keeps the level.
+    pub gain: f32,
+    /// A muted channel is rendered as a flat line.
+    pub muted: bool,
+}
+
+impl ChannelGain {
+    /// The effective linear factor: `0.0` if muted, otherwise [`Self::gain`].
+    pub const fn factor(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.gain
+        }
+    }
+
+    /// Returns the gain of the channel with the given index. Channels without an entry
+    /// in `gains` keep their level.
+    pub fn for_channel(gains: &[Self], channel: usize) -> Self {
+        gains.get(channel).copied().unwrap_or_default()
+    }
+}
+
+impl Default for ChannelGain {
+    fn default() -> Self {
+        Self {
+            gain: 1.0,
+            muted: false,
+        }
+    }
+}
@@ -0,0 +1,92 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+//! Static overview of audio data in a single PNG file: the waveform on top and the
+//! spectrum below, like the live window of [`crate::dynamic::window_top_btm`].
+
+use crate::spectrum::fft::samples_to_spectrum;
+use crate::spectrum::plotters_png_file::{draw_spectrum_chart, SpectrumChartConfig};
+use crate::waveform::plotters_png_file::{draw_chart as draw_waveform_chart, WaveformChartConfig};
+use crate::waveform::png_file::ChannelSamples;
+use plotters::prelude::*;
+use std::path::PathBuf;
+
+/// Number of samples per FFT used by [`overview_png_visualize`].
+const FFT_SIZE: usize = 4096;
+/// Width of the PNG file.
+const WIDTH: u32 = 1500;
+/// Height of the PNG file; each chart gets one half.
+const HEIGHT: u32 = 1400;
+
+/// Visualizes mono audio data as waveform (upper half) and its average spectrum (lower
+/// half) in one PNG file using the "plotters" crate. Useful as single summary figure.
+///
+/// The charts are the same as of
+/// [`crate::waveform::plotters_png_file::waveform_static_plotters_png_visualize`] and
+/// [`crate::spectrum::plotters_png_file::spectrum_static_plotters_png_visualize`]. The
+/// spectrum is calculated with [`samples_to_spectrum`].
+///
+/// # Parameters
+/// - `samples` Mono audio samples in interval `[-1.0; 1.0]`.
+/// - `sample_rate` Sampling rate of the audio data, e.g. 44100.
+/// - `directory` Directory of the PNG file.
+/// - `filename` Name of the PNG file.
+pub fn overview_png_visualize(samples: &[f32], sample_rate: u32, directory: &str, filename: &str) {
+    let mut path = PathBuf::new();
+    path.push(directory);
+    path.push(filename);
+
+    let spectrum = samples_to_spectrum(samples, sample_rate, FFT_SIZE);
+
+    let root = BitMapBackend::new(&path, (WIDTH, HEIGHT)).into_drawing_area();
+    root.fill(&WHITE).unwrap();
+    let (top, btm) = root.split_vertically(HEIGHT / 2);
+    draw_waveform_chart(
+        &top,
+        ChannelSamples::mono(samples),
+        &WaveformChartConfig::default(),
+    );
+    draw_spectrum_chart(&btm, &spectrum, &SpectrumChartConfig::default());
+    root.present().unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::testutil::TEST_OUT_DIR;
+    use std::f32::consts::PI;
+
+    #[test]
+    fn test_overview_png_visualize() {
+        // 440 Hz plus a quieter 1000 Hz
+        let samples = (0..44100)
+            .map(|i| i as f32 / 44100.0)
+            .map(|t| 0.6 * (2.0 * PI * 440.0 * t).sin() + 0.3 * (2.0 * PI * 1000.0 * t).sin())
+            .collect::<Vec<_>>();
+        overview_png_visualize(&samples, 44100, TEST_OUT_DIR, "overview_440hz_1000hz.png");
+        assert!(PathBuf::from(TEST_OUT_DIR)
+            .join("overview_440hz_1000hz.png")
+            .exists());
+    }
+}
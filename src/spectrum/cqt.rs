@@ -0,0 +1,217 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Static constant-Q spectrum: frequency bins are logarithmically spaced and aligned
+//! to musical pitches. More useful than a linear FFT spectrum for music analysis.
+
+use crate::util::music::nearest_note;
+use crate::util::DEFAULT_FONT;
+use plotters::prelude::*;
+use std::f32::consts::PI;
+use std::path::PathBuf;
+
+/// Calculates a constant-Q spectrum of mono audio data.
+///
+/// The center frequencies of the bins are `f_min * 2^(k / bins_per_octave)` up to the
+/// Nyquist frequency. Each bin is analyzed with a Hann window whose length is inversely
+/// proportional to the frequency of the bin, so that all bins have the same quality
+/// factor `Q`. The audio data is analyzed in windows with 50% overlap. The returned
+/// magnitude of each bin is the average over all windows.
+///
+/// Returns pairs of `(center frequency, magnitude)`.
+///
+/// # Parameters
+/// - `samples` Mono audio samples in interval `[-1.0; 1.0]`.
+/// - `sample_rate` Sampling rate of the audio data, e.g. 44100.
+/// - `bins_per_octave` Number of bins per octave, e.g. 12 for one bin per semitone.
+/// - `f_min` Center frequency of the lowest bin in Hertz, e.g. 32.7 (C1).
+pub fn constant_q_spectrum(
+    samples: &[f32],
+    sample_rate: u32,
+    bins_per_octave: u32,
+    f_min: f32,
+) -> Vec<(f32, f32)> {
+    assert!(bins_per_octave > 0, "bins_per_octave must be positive!");
+    assert!(f_min > 0.0, "f_min must be positive!");
+
+    let nyquist = sample_rate as f32 / 2.0;
+    // quality factor: ratio of center frequency to bandwidth
+    let q = 1.0 / (2.0_f32.powf(1.0 / bins_per_octave as f32) - 1.0);
+
+    (0..)
+        .map(|k| f_min * 2.0_f32.powf(k as f32 / bins_per_octave as f32))
+        .take_while(|frequency| *frequency < nyquist)
+        .map(|frequency| {
+            let window_len = (q * sample_rate as f32 / frequency).ceil() as usize;
+            (frequency, cqt_bin_magnitude(samples, window_len, q))
+        })
+        .collect()
+}
+
+/// Calculates the average magnitude of one constant-Q bin over all windows of
+/// length `window_len` (50% overlap). Short audio data is zero-padded.
+fn cqt_bin_magnitude(samples: &[f32], window_len: usize, q: f32) -> f32 {
+    let hop = (window_len / 2).max(1);
+    let mut sum = 0.0;
+    let mut count = 0;
+    let mut begin = 0;
+    loop {
+        let mut re = 0.0;
+        let mut im = 0.0;
+        for n in 0..window_len {
+            let sample = samples.get(begin + n).copied().unwrap_or(0.0);
+            let hann = 0.5 - 0.5 * (2.0 * PI * n as f32 / window_len as f32).cos();
+            // the bin frequency makes exactly "q" periods in the window
+            let phase = 2.0 * PI * q * n as f32 / window_len as f32;
+            re += sample * hann * phase.cos();
+            im -= sample * hann * phase.sin();
+        }
+        sum += re.hypot(im) / window_len as f32;
+        count += 1;
+
+        begin += hop;
+        if begin + window_len > samples.len() {
+            break;
+        }
+    }
+    sum / count as f32
+}
+
+/// Visualizes the constant-Q spectrum of mono audio data in a PNG file.
+///
+/// The spectrum is calculated with [`constant_q_spectrum`] and drawn using the "plotters"
+/// crate. The x-axis is labeled with the notes at the octave boundaries.
+///
+/// # Parameters
+/// - `samples` Mono audio samples in interval `[-1.0; 1.0]`.
+/// - `sample_rate` Sampling rate of the audio data, e.g. 44100.
+/// - `bins_per_octave` Number of bins per octave, e.g. 12 for one bin per semitone.
+/// - `f_min` Center frequency of the lowest bin in Hertz, e.g. 32.7 (C1).
+/// - `directory` Directory of the PNG file.
+/// - `filename` Name of the PNG file.
+pub fn spectrum_cqt_png_visualize(
+    samples: &[f32],
+    sample_rate: u32,
+    bins_per_octave: u32,
+    f_min: f32,
+    directory: &str,
+    filename: &str,
+) {
+    let spectrum = constant_q_spectrum(samples, sample_rate, bins_per_octave, f_min);
+    assert!(
+        !spectrum.iter().any(|(_, mag)| mag.is_nan()),
+        "There are NAN-values in the spectrum!"
+    );
+
+    let mut path = PathBuf::new();
+    path.push(directory);
+    path.push(filename);
+
+    let max = spectrum
+        .iter()
+        .map(|(_, mag)| *mag)
+        .fold(0.0, f32::max)
+        // silence: keep a valid y-axis
+        .max(f32::EPSILON);
+    let bin_count = spectrum.len() as u32;
+
+    // one label per octave
+    let octave_bins = (0..bin_count)
+        .step_by(bins_per_octave as usize)
+        .collect::<Vec<_>>();
+    let bin_to_note = |bin: &u32| {
+        let frequency = f_min * 2.0_f32.powf(*bin as f32 / bins_per_octave as f32);
+        nearest_note(frequency).to_string()
+    };
+
+    let root = BitMapBackend::new(&path, (1200, 700)).into_drawing_area();
+    root.fill(&WHITE).unwrap();
+    let mut chart = ChartBuilder::on(&root)
+        .caption("constant-Q spectrum", (DEFAULT_FONT, 20).into_font())
+        .margin(5)
+        .x_label_area_size(60)
+        .y_label_area_size(60)
+        .build_cartesian_2d((0..bin_count).with_key_points(octave_bins), 0.0..max)
+        .unwrap();
+
+    chart
+        .configure_mesh()
+        .x_label_formatter(&bin_to_note)
+        .x_desc("note")
+        .y_desc("magnitude")
+        .draw()
+        .unwrap();
+
+    chart
+        .draw_series(spectrum.iter().enumerate().map(|(bin, (_, mag))| {
+            Rectangle::new([(bin as u32, 0.0), (bin as u32 + 1, *mag)], RED.filled())
+        }))
+        .unwrap();
+
+    root.present().unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::testutil::TEST_OUT_DIR;
+
+    fn sine(frequency: f32, sample_rate: u32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| i as f32 / sample_rate as f32)
+            .map(|t| (t * frequency * 2.0 * PI).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_constant_q_spectrum_finds_a4() {
+        let samples = sine(440.0, 44100, 44100 / 2);
+        let spectrum = constant_q_spectrum(&samples, 44100, 12, 32.70);
+        let (peak_frequency, _) = spectrum
+            .iter()
+            .max_by(|(_, m1), (_, m2)| m1.partial_cmp(m2).unwrap())
+            .unwrap();
+        assert_eq!(nearest_note(*peak_frequency).to_string(), "A4");
+        // bins are semitones up to the nyquist frequency
+        assert_eq!(spectrum.len(), 113);
+    }
+
+    #[test]
+    fn test_spectrum_cqt_png_visualize() {
+        // A major chord
+        let samples = [220.0, 277.18, 329.63]
+            .iter()
+            .map(|frequency| sine(*frequency, 44100, 44100 / 2))
+            .fold(vec![0.0; 44100 / 2], |acc, x| {
+                acc.iter().zip(x.iter()).map(|(a, b)| a + b / 3.0).collect()
+            });
+        spectrum_cqt_png_visualize(
+            &samples,
+            44100,
+            12,
+            32.70,
+            TEST_OUT_DIR,
+            "spectrum_cqt_a_major_chord.png",
+        );
+    }
+}
@@ -0,0 +1,192 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Difference of two spectra, e.g. before and after an audio effect. Shows what changed
+//! more clearly than two overlaid spectra.
+
+use crate::util::DEFAULT_FONT;
+use plotters::prelude::*;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Color of the regions where the magnitude increased.
+const INCREASE_COLOR: RGBColor = RGBColor(0, 160, 0);
+/// Color of the regions where the magnitude decreased.
+const DECREASE_COLOR: RGBColor = RED;
+
+/// Calculates the signed difference `after - before` of two spectra per frequency.
+/// A frequency that is only present in one of both spectra has a magnitude of zero
+/// in the other one.
+pub fn spectrum_difference(
+    before: &BTreeMap<u32, f32>,
+    after: &BTreeMap<u32, f32>,
+) -> BTreeMap<u32, f32> {
+    before
+        .keys()
+        .chain(after.keys())
+        .map(|frequency| {
+            let before = before.get(frequency).copied().unwrap_or(0.0);
+            let after = after.get(frequency).copied().unwrap_or(0.0);
+            (*frequency, after - before)
+        })
+        .collect()
+}
+
+/// Visualizes the difference `after - before` of two spectra in a PNG file using the
+/// "plotters" crate.
+///
+/// The difference is calculated with [`spectrum_difference`] and drawn around a zero
+/// line. Regions where the magnitude increased are shaded green, regions where it
+/// decreased are shaded red.
+///
+/// # Parameters
+/// - `before` Spectrum before the change, e.g. the original audio data.
+/// - `after` Spectrum after the change, e.g. the filtered audio data.
+/// - `directory` Directory of the PNG file.
+/// - `filename` Name of the PNG file.
+pub fn spectrum_diff_png_visualize(
+    before: &BTreeMap<u32, f32>,
+    after: &BTreeMap<u32, f32>,
+    directory: &str,
+    filename: &str,
+) {
+    let difference = spectrum_difference(before, after);
+    assert!(
+        !difference.values().any(|diff| diff.is_nan()),
+        "There are NAN-values in the spectrum!"
+    );
+
+    let mut path = PathBuf::new();
+    path.push(directory);
+    path.push(filename);
+
+    let max_frequency = difference.keys().last().copied().unwrap_or(0).max(1) as f32;
+    let max_abs = difference
+        .values()
+        .map(|diff| diff.abs())
+        .fold(0.0, f32::max)
+        // no difference: keep a valid y-axis
+        .max(f32::EPSILON);
+    let points = difference
+        .iter()
+        .map(|(frequency, diff)| (*frequency as f32, *diff))
+        .collect::<Vec<_>>();
+
+    let width = (difference.len() as u32).max(700);
+    let root = BitMapBackend::new(&path, (width, 700)).into_drawing_area();
+    root.fill(&WHITE).unwrap();
+    let mut chart = ChartBuilder::on(&root)
+        .caption(
+            "spectrum difference (after - before)",
+            (DEFAULT_FONT, 20).into_font(),
+        )
+        .margin(5)
+        .x_label_area_size(60)
+        .y_label_area_size(60)
+        .build_cartesian_2d(0.0..max_frequency, -max_abs..max_abs)
+        .unwrap();
+
+    chart
+        .configure_mesh()
+        .x_desc("frequency (Hz)")
+        .y_desc("magnitude difference")
+        .draw()
+        .unwrap();
+
+    // each region is filled between the zero line and the difference
+    chart
+        .draw_series(AreaSeries::new(
+            points
+                .iter()
+                .map(|(frequency, diff)| (*frequency, diff.max(0.0))),
+            0.0,
+            INCREASE_COLOR.mix(0.4),
+        ))
+        .unwrap();
+    chart
+        .draw_series(AreaSeries::new(
+            points
+                .iter()
+                .map(|(frequency, diff)| (*frequency, diff.min(0.0))),
+            0.0,
+            DECREASE_COLOR.mix(0.4),
+        ))
+        .unwrap();
+    chart
+        .draw_series(LineSeries::new(
+            vec![(0.0, 0.0), (max_frequency, 0.0)],
+            &BLACK,
+        ))
+        .unwrap();
+    chart
+        .draw_series(LineSeries::new(points.iter().copied(), &BLACK.mix(0.6)))
+        .unwrap();
+
+    root.present().unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::testutil::TEST_OUT_DIR;
+
+    #[test]
+    fn test_spectrum_difference() {
+        let before = [(100, 1.0), (200, 0.5), (300, 0.25)]
+            .iter()
+            .copied()
+            .collect::<BTreeMap<u32, f32>>();
+        let after = [(100, 0.5), (200, 0.75), (400, 1.0)]
+            .iter()
+            .copied()
+            .collect::<BTreeMap<u32, f32>>();
+        let difference = spectrum_difference(&before, &after);
+        assert_eq!(
+            difference.into_iter().collect::<Vec<_>>(),
+            vec![(100, -0.5), (200, 0.25), (300, -0.25), (400, 1.0)]
+        );
+    }
+
+    #[test]
+    fn test_spectrum_diff_png_visualize() {
+        // a lowpass-like change: the highs are attenuated, a resonance at 1 kHz is boosted
+        let before = (0..=20000)
+            .step_by(20)
+            .map(|frequency| (frequency, 1.0 - frequency as f32 / 40000.0))
+            .collect::<BTreeMap<u32, f32>>();
+        let after = before
+            .iter()
+            .map(|(frequency, magnitude)| {
+                let gain = if *frequency > 5000 {
+                    5000.0 / *frequency as f32
+                } else if (900..=1100).contains(frequency) {
+                    1.3
+                } else {
+                    1.0
+                };
+                (*frequency, magnitude * gain)
+            })
+            .collect::<BTreeMap<u32, f32>>();
+        spectrum_diff_png_visualize(&before, &after, TEST_OUT_DIR, "spectrum_diff.png");
+    }
+}
@@ -0,0 +1,87 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Simple spectral features for timbre characterization, e.g. for MIR experiments.
+//!
+//! They can be annotated in the spectrum chart, see
+//! [`crate::spectrum::plotters_png_file::SpectrumChartConfig::show_features`].
+
+use std::collections::BTreeMap;
+
+/// Calculates the spectral centroid ("center of mass" of the spectrum), i.e. the
+/// magnitude-weighted mean frequency. Returns `None`, if all magnitudes are zero.
+pub fn spectral_centroid(frequency_spectrum: &BTreeMap<u32, f32>) -> Option<f32> {
+    let magnitude_sum = frequency_spectrum.values().sum::<f32>();
+    if magnitude_sum <= 0.0 {
+        return None;
+    }
+    let weighted_sum = frequency_spectrum
+        .iter()
+        .map(|(frequency, magnitude)| *frequency as f32 * magnitude)
+        .sum::<f32>();
+    Some(weighted_sum / magnitude_sum)
+}
+
+/// Calculates the spectral rolloff, i.e. the lowest frequency below which `fraction`
+/// (e.g. `0.85`) of the total magnitude of the spectrum lies. Returns `None`, if all
+/// magnitudes are zero.
+pub fn spectral_rolloff(frequency_spectrum: &BTreeMap<u32, f32>, fraction: f32) -> Option<f32> {
+    assert!(
+        (0.0..=1.0).contains(&fraction),
+        "fraction must be in interval [0.0; 1.0]!"
+    );
+    let magnitude_sum = frequency_spectrum.values().sum::<f32>();
+    if magnitude_sum <= 0.0 {
+        return None;
+    }
+    let threshold = magnitude_sum * fraction;
+    let mut cumulative_sum = 0.0;
+    frequency_spectrum
+        .iter()
+        .find(|(_, magnitude)| {
+            cumulative_sum += **magnitude;
+            cumulative_sum >= threshold
+        })
+        .map(|(frequency, _)| *frequency as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spectral_centroid_and_rolloff() {
+        let spectrum = [(100, 1.0), (200, 2.0), (300, 1.0), (400, 0.0)]
+            .into_iter()
+            .collect::<BTreeMap<_, _>>();
+        assert_eq!(spectral_centroid(&spectrum), Some(200.0));
+        assert_eq!(spectral_rolloff(&spectrum, 0.85), Some(300.0));
+        assert_eq!(spectral_rolloff(&spectrum, 0.5), Some(200.0));
+
+        let silence = [(100, 0.0), (200, 0.0)]
+            .into_iter()
+            .collect::<BTreeMap<_, _>>();
+        assert_eq!(spectral_centroid(&silence), None);
+        assert_eq!(spectral_rolloff(&silence, 0.85), None);
+    }
+}
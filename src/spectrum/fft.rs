@@ -0,0 +1,118 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Frequency spectrum calculation with the [`spectrum_analyzer`] crate. The results
+//! can be passed directly to the spectrum visualization functions of this module.
+
+use crate::spectrum::spectrogram::compute_spectrogram;
+use std::collections::BTreeMap;
+
+/// Calculates the frequency spectrum of mono audio data.
+///
+/// Long audio data is split into chunks of `fft_size` samples. The returned spectrum
+/// is the average over the spectra of all chunks. If there are less than `fft_size`
+/// samples, the data is zero-padded.
+///
+/// Returns a map from frequency (in Hertz) to magnitude.
+///
+/// # Parameters
+/// - `samples` Mono audio samples in interval `[-1.0; 1.0]`.
+/// - `sample_rate` Sampling rate of the audio data, e.g. 44100.
+/// - `fft_size` Number of samples per FFT. Must be a power of two.
+pub fn samples_to_spectrum(
+    samples: &[f32],
+    sample_rate: u32,
+    fft_size: usize,
+) -> BTreeMap<u32, f32> {
+    samples_to_spectrum_with_hop(samples, sample_rate, fft_size, fft_size)
+}
+
+/// Like [`samples_to_spectrum`] but with overlapping chunks.
+///
+/// The start of each chunk is `hop_size` samples after the start of the previous chunk,
+/// e.g. `fft_size / 2` for 50% overlap. See [`compute_spectrogram`].
+pub fn samples_to_spectrum_with_hop(
+    samples: &[f32],
+    sample_rate: u32,
+    fft_size: usize,
+    hop_size: usize,
+) -> BTreeMap<u32, f32> {
+    let (frames, frequencies) = compute_spectrogram(samples, sample_rate, fft_size, hop_size);
+
+    let mut spectrum = BTreeMap::new();
+    for frame in &frames {
+        for (frequency, magnitude) in frequencies.iter().zip(frame) {
+            *spectrum.entry(*frequency as u32).or_insert(0.0) += magnitude;
+        }
+    }
+
+    let frame_count = frames.len() as f32;
+    spectrum
+        .values_mut()
+        .for_each(|magnitude| *magnitude /= frame_count);
+    spectrum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_samples_to_spectrum_finds_peak() {
+        let sample_rate = 44100;
+        let samples = (0..sample_rate)
+            .map(|i| i as f32 / sample_rate as f32)
+            .map(|t| (t * 1000.0 * 2.0 * std::f32::consts::PI).sin())
+            .collect::<Vec<_>>();
+
+        let spectrum = samples_to_spectrum(&samples, sample_rate, 4096);
+        let (peak_frequency, _) = spectrum
+            .iter()
+            .max_by(|(_, m1), (_, m2)| m1.partial_cmp(m2).unwrap())
+            .unwrap();
+        assert!((*peak_frequency as i32 - 1000).abs() < 15);
+    }
+
+    #[test]
+    fn test_samples_to_spectrum_short_input() {
+        let spectrum = samples_to_spectrum(&[0.0, 1.0, 0.0, -1.0], 44100, 1024);
+        assert_eq!(spectrum.len(), 1024 / 2 + 1);
+    }
+
+    #[test]
+    fn test_samples_to_spectrum_with_hop_overlap() {
+        let sample_rate = 44100;
+        let samples = (0..sample_rate)
+            .map(|i| i as f32 / sample_rate as f32)
+            .map(|t| (t * 1000.0 * 2.0 * std::f32::consts::PI).sin())
+            .collect::<Vec<_>>();
+
+        let spectrum = samples_to_spectrum(&samples, sample_rate, 4096);
+        let overlapping = samples_to_spectrum_with_hop(&samples, sample_rate, 4096, 2048);
+        assert_eq!(spectrum.len(), overlapping.len());
+        // a stationary signal has (nearly) the same average spectrum
+        for (m1, m2) in spectrum.values().zip(overlapping.values()) {
+            assert!((m1 - m2).abs() < 0.01);
+        }
+    }
+}
@@ -26,5 +26,13 @@ SOFTWARE.
 //! This module focuses on static visualization. For dynamic visualization,
 //! look into the [`crate::dynamic`] module + corresponding examples in `examples/`.
 
+pub mod cqt;
+pub mod diff;
+pub mod features;
+pub mod fft;
+pub mod normalize;
+pub mod peaks;
 pub mod plotters_png_file;
 pub mod png_file;
+pub mod smooth;
+pub mod spectrogram;
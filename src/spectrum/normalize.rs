@@ -0,0 +1,166 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Normalization of frequency spectra, e.g. to compare spectra of different recordings.
+
+use std::collections::BTreeMap;
+use std::iter::Sum;
+use std::ops::{Add, Div};
+
+/// How the magnitudes of a spectrum are normalized. See [`normalize_spectrum`].
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum SpectrumNormalize {
+    /// The magnitudes are not changed.
+    #[default]
+    None,
+    /// The magnitudes are divided by their median.
+    Median,
+    /// The magnitudes are divided by their maximum, i.e. the peak becomes 1.0.
+    Max,
+    /// The magnitudes are divided by their sum, i.e. they sum up to 1.0.
+    UnitArea,
+}
+
+/// Normalizes the magnitudes of the spectrum according to `mode`.
+///
+/// If the divisor is zero (e.g. silence), the spectrum is returned unchanged.
+pub fn normalize_spectrum(
+    frequency_spectrum: &BTreeMap<u32, f32>,
+    mode: SpectrumNormalize,
+) -> BTreeMap<u32, f32> {
+    normalize_magnitudes(frequency_spectrum, mode)
+}
+
+/// Like [`normalize_spectrum`] but for `f64` magnitudes.
+///
+/// Avoids the loss of precision of `f32` for spectra with a high dynamic range,
+/// i.e. very large or very small magnitudes.
+pub fn normalize_spectrum_f64(
+    frequency_spectrum: &BTreeMap<u32, f64>,
+    mode: SpectrumNormalize,
+) -> BTreeMap<u32, f64> {
+    normalize_magnitudes(frequency_spectrum, mode)
+}
+
+/// Implementation of [`normalize_spectrum`] for `f32` and `f64` magnitudes. The frequencies
+/// may also be signed, e.g. for a two-sided spectrum.
+pub(crate) fn normalize_magnitudes<F, T>(
+    frequency_spectrum: &BTreeMap<F, T>,
+    mode: SpectrumNormalize,
+) -> BTreeMap<F, T>
+where
+    F: Copy + Ord,
+    T: Copy + PartialOrd + From<f32> + Add<Output = T> + Div<Output = T> + Sum,
+{
+    let zero = T::from(0.0);
+    let one = T::from(1.0);
+    let divisor = match mode {
+        SpectrumNormalize::None => one,
+        SpectrumNormalize::Median => {
+            let mut magnitudes = frequency_spectrum.values().copied().collect::<Vec<_>>();
+            magnitudes.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            match magnitudes.len() {
+                0 => one,
+                len if len % 2 == 0 => {
+                    (magnitudes[len / 2 - 1] + magnitudes[len / 2]) / T::from(2.0)
+                }
+                len => magnitudes[len / 2],
+            }
+        }
+        SpectrumNormalize::Max => {
+            frequency_spectrum
+                .values()
+                .copied()
+                .fold(
+                    zero,
+                    |max, magnitude| if magnitude > max { magnitude } else { max },
+                )
+        }
+        SpectrumNormalize::UnitArea => frequency_spectrum.values().copied().sum(),
+    };
+
+    if divisor == zero {
+        return frequency_spectrum.clone();
+    }
+    frequency_spectrum
+        .iter()
+        .map(|(frequency, magnitude)| (*frequency, *magnitude / divisor))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_spectrum() {
+        let spectrum = [(10, 1.0), (20, 4.0), (30, 2.0), (40, 1.0)]
+            .into_iter()
+            .collect::<BTreeMap<_, _>>();
+        let magnitudes = |mode| {
+            normalize_spectrum(&spectrum, mode)
+                .values()
+                .copied()
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            magnitudes(SpectrumNormalize::None),
+            vec![1.0, 4.0, 2.0, 1.0]
+        );
+        assert_eq!(
+            magnitudes(SpectrumNormalize::Median),
+            vec![1.0 / 1.5, 4.0 / 1.5, 2.0 / 1.5, 1.0 / 1.5]
+        );
+        assert_eq!(
+            magnitudes(SpectrumNormalize::Max),
+            vec![0.25, 1.0, 0.5, 0.25]
+        );
+        assert_eq!(
+            magnitudes(SpectrumNormalize::UnitArea),
+            vec![0.125, 0.5, 0.25, 0.125]
+        );
+    }
+
+    #[test]
+    fn test_normalize_silence() {
+        let silence = [(10, 0.0), (20, 0.0)]
+            .into_iter()
+            .collect::<BTreeMap<_, _>>();
+        assert_eq!(
+            normalize_spectrum(&silence, SpectrumNormalize::Max),
+            silence
+        );
+    }
+
+    #[test]
+    fn test_normalize_spectrum_f64() {
+        // the normalized small magnitude would be zero with f32
+        let spectrum = [(10, 1e-30), (20, 1e30)]
+            .into_iter()
+            .collect::<BTreeMap<_, _>>();
+        let normalized = normalize_spectrum_f64(&spectrum, SpectrumNormalize::Max);
+        assert_eq!(normalized[&20], 1.0);
+        assert!((normalized[&10] / 1e-60 - 1.0).abs() < 1e-9);
+    }
+}
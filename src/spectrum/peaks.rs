@@ -0,0 +1,112 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Peak detection in frequency spectra. The detected peaks can be highlighted (with a color
+//! per peak) in [`crate::spectrum::png_file::spectrum_static_png_visualize`].
+
+use std::collections::BTreeMap;
+
+/// Finds the peaks (local maxima) of a frequency spectrum and returns their frequencies.
+///
+/// On real, noisy data there are many tiny local maxima. Therefore, only peaks that
+/// fulfill both thresholds are returned:
+/// - `min_prominence` The minimum prominence of a peak, i.e. how much the peak rises above
+///   its neighborhood (same unit as the magnitudes). The neighborhood of a peak reaches to
+///   the next higher value (or the border of the spectrum) on each side. The prominence is
+///   the difference of the peak to the higher one of the two minima in the neighborhood.
+/// - `min_magnitude_ratio` The minimum magnitude of a peak as fraction of the global
+///   maximum of the spectrum, i.e. a value in interval `[0.0; 1.0]`.
+///
+/// The first and the last value of the spectrum are never reported as peak.
+pub fn find_peaks(
+    frequency_spectrum: &BTreeMap<u32, f32>,
+    min_prominence: f32,
+    min_magnitude_ratio: f32,
+) -> Vec<f32> {
+    let data = frequency_spectrum
+        .iter()
+        .map(|(fr, mag)| (*fr, *mag))
+        .collect::<Vec<_>>();
+    let global_max = data.iter().map(|(_, mag)| *mag).fold(0.0, f32::max);
+    let min_magnitude = global_max * min_magnitude_ratio;
+
+    let mut peaks = vec![];
+    for i in 1..data.len().saturating_sub(1) {
+        let mag = data[i].1;
+        // strict on the left side: a plateau is only reported once
+        let is_local_max = mag > data[i - 1].1 && mag >= data[i + 1].1;
+        if !is_local_max || mag < min_magnitude {
+            continue;
+        }
+
+        let left_min = data[..i]
+            .iter()
+            .rev()
+            .take_while(|(_, x)| *x <= mag)
+            .map(|(_, x)| *x)
+            .fold(mag, f32::min);
+        let right_min = data[i + 1..]
+            .iter()
+            .take_while(|(_, x)| *x <= mag)
+            .map(|(_, x)| *x)
+            .fold(mag, f32::min);
+        let prominence = mag - left_min.max(right_min);
+
+        if prominence >= min_prominence {
+            peaks.push(data[i].0 as f32);
+        }
+    }
+    peaks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_peaks_ignores_small_wiggles() {
+        let mut spectrum = BTreeMap::new();
+        let magnitudes = [
+            0.0, 2.0, 1.0, 3.0, 2.0, 20.0, 100.0, 30.0, 31.0, 29.0, 5.0, 6.0, 5.0, 60.0, 10.0, 0.0,
+        ];
+        for (i, mag) in magnitudes.iter().enumerate() {
+            spectrum.insert(i as u32 * 10, *mag);
+        }
+
+        // all local maxima
+        assert_eq!(
+            find_peaks(&spectrum, 0.0, 0.0),
+            vec![10.0, 30.0, 60.0, 80.0, 110.0, 130.0]
+        );
+        // only the peaks with a notable prominence
+        assert_eq!(find_peaks(&spectrum, 10.0, 0.0), vec![60.0, 130.0]);
+        // only the peaks with a notable magnitude
+        assert_eq!(find_peaks(&spectrum, 0.0, 0.5), vec![60.0, 130.0]);
+        assert_eq!(find_peaks(&spectrum, 10.0, 0.7), vec![60.0]);
+    }
+
+    #[test]
+    fn test_find_peaks_empty() {
+        assert!(find_peaks(&BTreeMap::new(), 0.0, 0.0).is_empty());
+    }
+}
@@ -1,35 +1,89 @@
-//! Static spectrum analysis: print spectrum to PNG file.
+//! Static spectrum analysis: print spectrum to PNG file or into an in-memory buffer.
 
+use crate::spectrum::features::{spectral_centroid, spectral_rolloff};
+use crate::spectrum::normalize::{
+    normalize_magnitudes, normalize_spectrum, normalize_spectrum_f64, SpectrumNormalize,
+};
+use crate::spectrum::smooth::{smooth_spectrum, smooth_spectrum_f64};
+use crate::util::{MeshMode, DEFAULT_FONT};
+use plotters::coord::Shift;
 use plotters::prelude::*;
 use std::collections::BTreeMap;
+use std::fmt::{Debug, Formatter};
+use std::ops::Range;
 use std::path::PathBuf;
 
+/// Configuration for [`spectrum_static_plotters_png_visualize_with_config`].
+/// Use [`SpectrumChartConfig::default`] and override the fields you need.
+#[derive(Clone, Default)]
+pub struct SpectrumChartConfig<'a> {
+    /// Formats the labels of the x-axis (frequency), e.g. `12000.0` as `"12k"`.
+    /// If `None`, the default formatting of "plotters" is used.
+    pub x_label_formatter: Option<&'a dyn Fn(f32) -> String>,
+    /// Formats the labels of the y-axis (magnitude), e.g. as dB.
+    /// If `None`, the default formatting of "plotters" is used.
+    pub y_label_formatter: Option<&'a dyn Fn(f32) -> String>,
+    /// Draws labeled vertical markers at the spectral centroid and at the 85% spectral
+    /// rolloff frequency. See [`crate::spectrum::features`].
+    pub show_features: bool,
+    /// How the magnitudes are normalized before they are drawn. Default is
+    /// [`SpectrumNormalize::None`].
+    pub normalize: SpectrumNormalize,
+    /// Smooths the magnitudes with a moving average over the given number of frequency
+    /// bins before they are normalized and drawn, e.g. 5 for noisy spectra in reports. See
+    /// [`smooth_spectrum`]. Default is `None`, i.e. the raw spectrum.
+    pub smooth_window: Option<usize>,
+    /// Caption of the chart, e.g. for figures in reports. If `None`, the caption is
+    /// `"y=f magnitudes of sample"`. An empty string suppresses the caption.
+    pub title: Option<&'a str>,
+    /// Color of the caption. If `None`, the caption is black.
+    pub caption_color: Option<RGBColor>,
+    /// Grid of the chart. Default is [`MeshMode::Full`].
+    pub mesh: MeshMode,
+    /// Font family of the caption and the labels, e.g. `"DejaVu Sans"`, if [`DEFAULT_FONT`]
+    /// isn't installed. If `None`, [`DEFAULT_FONT`] is used. The family must be installed on
+    /// the system; no font is bundled and there is no fallback, i.e. the labels stay blank.
+    pub font: Option<String>,
+}
+
+impl Debug for SpectrumChartConfig<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SpectrumChartConfig")
+            .field("x_label_formatter", &self.x_label_formatter.is_some())
+            .field("y_label_formatter", &self.y_label_formatter.is_some())
+            .field("show_features", &self.show_features)
+            .field("normalize", &self.normalize)
+            .field("smooth_window", &self.smooth_window)
+            .field("title", &self.title)
+            .field("caption_color", &self.caption_color)
+            .field("mesh", &self.mesh)
+            .field("font", &self.font)
+            .finish()
+    }
+}
+
+/// Visualizes the spectrum in a PNG file using the "plotters" crate.
 pub fn spectrum_static_plotters_png_visualize(
     frequency_spectrum: &BTreeMap<u32, f32>,
     directory: &str,
     filename: &str,
 ) {
-    // assert no NAN
-    assert!(
-        !frequency_spectrum.iter().any(|(_, f)| f.is_nan()),
-        "There are NAN-values in the spectrum!"
-    );
-
-    // find maximum for graphics scaling
-    let mut max = 0.0;
-    for mag in frequency_spectrum.values() {
-        if *mag > max {
-            max = *mag;
-        }
-    }
-
-    let max_frequency = *frequency_spectrum
-        .iter()
-        .skip(frequency_spectrum.len() - 2)
-        .last()
-        .unwrap()
-        .0;
+    spectrum_static_plotters_png_visualize_with_config(
+        frequency_spectrum,
+        directory,
+        filename,
+        &SpectrumChartConfig::default(),
+    )
+}
 
+/// Like [`spectrum_static_plotters_png_visualize`] but with additional configuration,
+/// e.g. custom formatting of the axis labels. See [`SpectrumChartConfig`].
+pub fn spectrum_static_plotters_png_visualize_with_config(
+    frequency_spectrum: &BTreeMap<u32, f32>,
+    directory: &str,
+    filename: &str,
+    cfg: &SpectrumChartConfig,
+) {
     let mut path = PathBuf::new();
     path.push(directory);
     path.push(filename);
@@ -46,35 +100,281 @@ pub fn spectrum_static_plotters_png_visualize(
     };
 
     let root = BitMapBackend::new(&path, (width, height)).into_drawing_area();
+    draw_spectrum_chart(&root, frequency_spectrum, cfg);
+    root.present().unwrap();
+}
+
+/// Like [`spectrum_static_plotters_png_visualize_with_config`] but for `f64` magnitudes.
+///
+/// The normalization ([`SpectrumChartConfig::normalize`]) is done with `f64`, so that
+/// spectra with a high dynamic range don't lose precision. Only the normalized
+/// magnitudes are converted to `f32` for drawing.
+pub fn spectrum_static_plotters_png_visualize_f64(
+    frequency_spectrum: &BTreeMap<u32, f64>,
+    directory: &str,
+    filename: &str,
+    cfg: &SpectrumChartConfig,
+) {
+    // assert no NAN
+    assert!(
+        !frequency_spectrum.iter().any(|(_, f)| f.is_nan()),
+        "There are NAN-values in the spectrum!"
+    );
+    // a window of one bin keeps the spectrum unchanged
+    let frequency_spectrum =
+        &smooth_spectrum_f64(frequency_spectrum, cfg.smooth_window.unwrap_or(1));
+    let frequency_spectrum = normalize_spectrum_f64(frequency_spectrum, cfg.normalize)
+        .into_iter()
+        .map(|(frequency, magnitude)| (frequency, magnitude as f32))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut cfg = cfg.clone();
+    cfg.normalize = SpectrumNormalize::None;
+    cfg.smooth_window = None;
+    spectrum_static_plotters_png_visualize_with_config(
+        &frequency_spectrum,
+        directory,
+        filename,
+        &cfg,
+    )
+}
+
+/// Renders the same chart as [`spectrum_static_plotters_png_visualize`] but into
+/// an in-memory buffer instead of a file.
+///
+/// Useful to embed the visualization into an own application (e.g. a GUI), that
+/// takes care of displaying the image.
+///
+/// Returns the RGB-bytes of the image (`width * height * 3` bytes, row by row). An empty
+/// spectrum, e.g. from [`crate::util::crop_spectrum`], results in a chart without data.
+pub fn render_spectrum_to_buffer(
+    frequency_spectrum: &BTreeMap<u32, f32>,
+    width: u32,
+    height: u32,
+) -> Vec<u8> {
+    render_spectrum_to_buffer_with_config(
+        frequency_spectrum,
+        width,
+        height,
+        &SpectrumChartConfig::default(),
+    )
+}
+
+/// Like [`render_spectrum_to_buffer`] but with additional configuration, e.g. the
+/// normalization of the magnitudes. See [`SpectrumChartConfig`].
+pub fn render_spectrum_to_buffer_with_config(
+    frequency_spectrum: &BTreeMap<u32, f32>,
+    width: u32,
+    height: u32,
+    cfg: &SpectrumChartConfig,
+) -> Vec<u8> {
+    let mut rgb_buf = vec![0; width as usize * height as usize * 3];
+    {
+        let root = BitMapBackend::with_buffer(&mut rgb_buf, (width, height)).into_drawing_area();
+        draw_spectrum_chart(&root, frequency_spectrum, cfg);
+        root.present().unwrap();
+    }
+    rgb_buf
+}
+
+/// Visualizes a full (two-sided) spectrum with signed frequencies in a PNG file using the
+/// "plotters" crate.
+///
+/// Useful to show the structure of the DFT: the x-axis is symmetric, i.e. DC (0 Hz) is in
+/// the middle with the negative frequencies on the left and the positive frequencies on the
+/// right. DC is marked by a vertical line.
+pub fn spectrum_static_plotters_png_visualize_symmetric(
+    frequency_spectrum: &BTreeMap<i32, f32>,
+    directory: &str,
+    filename: &str,
+) {
+    spectrum_static_plotters_png_visualize_symmetric_with_config(
+        frequency_spectrum,
+        directory,
+        filename,
+        &SpectrumChartConfig::default(),
+    )
+}
+
+/// Like [`spectrum_static_plotters_png_visualize_symmetric`] but with additional
+/// configuration, e.g. the normalization of the magnitudes.
+///
+/// See [`SpectrumChartConfig`]; [`SpectrumChartConfig::show_features`] doesn't apply to
+/// two-sided spectra.
+pub fn spectrum_static_plotters_png_visualize_symmetric_with_config(
+    frequency_spectrum: &BTreeMap<i32, f32>,
+    directory: &str,
+    filename: &str,
+    cfg: &SpectrumChartConfig,
+) {
+    // assert no NAN
+    assert!(
+        !frequency_spectrum.iter().any(|(_, f)| f.is_nan()),
+        "There are NAN-values in the spectrum!"
+    );
+    let frequency_spectrum = &normalize_magnitudes(frequency_spectrum, cfg.normalize);
+
+    let mut path = PathBuf::new();
+    path.push(directory);
+    path.push(filename);
+
+    let width = (frequency_spectrum.len() as u32).max(700);
+    let height = 700;
+
+    // both sides of the x-axis must have the same length, even if the spectrum isn't symmetric
+    let max_abs_frequency = frequency_spectrum
+        .keys()
+        .map(|frequency| frequency.unsigned_abs())
+        .max()
+        .unwrap_or(0)
+        .max(1) as f32;
+
+    let points = frequency_spectrum
+        .iter()
+        .map(|(frequency, magnitude)| (*frequency as f32, *magnitude))
+        .collect::<Vec<_>>();
+
+    let root = BitMapBackend::new(&path, (width, height)).into_drawing_area();
+    draw_magnitudes_chart(
+        &root,
+        -max_abs_frequency..max_abs_frequency,
+        &points,
+        &[(0.0, "DC".to_string())],
+        cfg,
+    );
+    root.present().unwrap();
+}
+
+/// Draws the spectrum chart into the given drawing area. Independent of
+/// the backend, i.e. whether the image ends up in a file or in a buffer.
+pub(crate) fn draw_spectrum_chart<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    frequency_spectrum: &BTreeMap<u32, f32>,
+    cfg: &SpectrumChartConfig,
+) {
+    // assert no NAN
+    assert!(
+        !frequency_spectrum.iter().any(|(_, f)| f.is_nan()),
+        "There are NAN-values in the spectrum!"
+    );
+    // a window of one bin keeps the spectrum unchanged
+    let frequency_spectrum = &smooth_spectrum(frequency_spectrum, cfg.smooth_window.unwrap_or(1));
+    let frequency_spectrum = &normalize_spectrum(frequency_spectrum, cfg.normalize);
+
+    let max_frequency = frequency_spectrum.keys().next_back().copied().unwrap_or(0);
+    // empty spectrum or a single bin at DC: keep a valid x-axis for an empty (flat) chart
+    let max_frequency = if max_frequency == 0 {
+        1.0
+    } else {
+        max_frequency as f32
+    };
+
+    let points = frequency_spectrum
+        .iter()
+        .map(|(frequency, magnitude)| (*frequency as f32, *magnitude))
+        .collect::<Vec<_>>();
+
+    let mut markers = Vec::new();
+    if cfg.show_features {
+        if let Some(centroid) = spectral_centroid(frequency_spectrum) {
+            markers.push((centroid, format!("centroid: {:.0} Hz", centroid)));
+        }
+        if let Some(rolloff) = spectral_rolloff(frequency_spectrum, 0.85) {
+            markers.push((rolloff, format!("85% rolloff: {:.0} Hz", rolloff)));
+        }
+    }
+
+    draw_magnitudes_chart(root, 0.0..max_frequency, &points, &markers, cfg);
+}
+
+/// Draws `(frequency, magnitude)` points as line chart into the given drawing area.
+/// Each `(frequency, label)` of `markers` is drawn as labeled vertical line.
+fn draw_magnitudes_chart<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    x_range: Range<f32>,
+    points: &[(f32, f32)],
+    markers: &[(f32, String)],
+    cfg: &SpectrumChartConfig,
+) {
+    // find maximum for graphics scaling
+    let mut max = 0.0;
+    for (_, mag) in points {
+        if *mag > max {
+            max = *mag;
+        }
+    }
+    // silence: keep a valid y-axis; the magnitudes form a flat baseline at zero
+    if max == 0.0 {
+        max = 1.0;
+    }
+
+    let font = cfg.font.as_deref().unwrap_or(DEFAULT_FONT);
     root.fill(&WHITE).unwrap();
-    let mut chart = ChartBuilder::on(&root)
-        .caption("y=f magnitudes of sample", ("sans-serif", 20).into_font())
+    let mut builder = ChartBuilder::on(root);
+    builder
         .margin(5)
         .x_label_area_size(60)
-        .y_label_area_size(60)
-        .build_cartesian_2d(0.0..(max_frequency as f32) /*.log10()*/, 0.0..max)
-        .unwrap();
+        .y_label_area_size(60);
+    let title = cfg.title.unwrap_or("y=f magnitudes of sample");
+    if !title.is_empty() {
+        builder.caption(
+            title,
+            (font, 20)
+                .into_font()
+                .color(&cfg.caption_color.unwrap_or(BLACK)),
+        );
+    }
+    let mut chart = builder.build_cartesian_2d(x_range, 0.0..max).unwrap();
+
+    // adapters for the signature that "plotters" expects
+    let x_label_formatter = cfg.x_label_formatter.map(|f| move |x: &f32| f(*x));
+    let y_label_formatter = cfg.y_label_formatter.map(|f| move |y: &f32| f(*y));
+    let mut mesh = chart.configure_mesh();
+    cfg.mesh.apply(&mut mesh);
+    // otherwise, "plotters" uses its default font for the labels
+    if cfg.font.is_some() {
+        mesh.label_style((font, 15));
+    }
+    if let Some(formatter) = &x_label_formatter {
+        mesh.x_label_formatter(formatter);
+    }
+    if let Some(formatter) = &y_label_formatter {
+        mesh.y_label_formatter(formatter);
+    }
+    mesh.draw().unwrap();
 
-    chart.configure_mesh().draw().unwrap();
+    for (i, (frequency, label)) in markers.iter().enumerate() {
+        chart
+            .draw_series(LineSeries::new(
+                vec![(*frequency, 0.0), (*frequency, max)],
+                BLACK.mix(0.5),
+            ))
+            .unwrap();
+        // labels of markers that are close to each other must not overlap
+        let label_y = max * (0.97 - 0.05 * i as f32);
+        chart
+            .draw_series(std::iter::once(Text::new(
+                format!(" {}", label),
+                (*frequency, label_y),
+                (font, 15).into_font(),
+            )))
+            .unwrap();
+    }
 
     chart
-        .draw_series(LineSeries::new(
-            // (-50..=50).map(|x| x as f32 / 50.0).map(|x| (x, x * x)),
-            frequency_spectrum
-                .iter()
-                .map(|(frequency, magnitude)| ((*frequency as f32) /*.log10()*/, *magnitude)),
-            &RED,
-        ))
+        .draw_series(LineSeries::new(points.iter().copied(), &RED))
         .unwrap()
         .label("frequency magnitude")
         .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], RED));
 
-    chart
-        .configure_series_labels()
+    let mut series_labels = chart.configure_series_labels();
+    series_labels
         .background_style(WHITE.mix(0.8))
-        .border_style(BLACK)
-        .draw()
-        .unwrap();
+        .border_style(BLACK);
+    if cfg.font.is_some() {
+        series_labels.label_font((font, 15));
+    }
+    series_labels.draw().unwrap();
 }
 
 #[cfg(test)]
@@ -110,6 +410,244 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_visualize_spectrum_plotters_custom_labels() {
+        let spectrum = (0..20000)
+            .step_by(100)
+            .map(|f| (f, 1.0 / (1.0 + (f as f32 - 12000.0).abs() / 1000.0)))
+            .collect::<BTreeMap<_, _>>();
+
+        let si_hz = |x: f32| {
+            if x >= 1000.0 {
+                format!("{}k Hz", x / 1000.0)
+            } else {
+                format!("{} Hz", x)
+            }
+        };
+        let db = |y: f32| format!("{:.1} dB", 20.0 * y.log10());
+        spectrum_static_plotters_png_visualize_with_config(
+            &spectrum,
+            TEST_OUT_DIR,
+            "spectrum_12khz_peak_plotters_visualization_custom_labels.png",
+            &SpectrumChartConfig {
+                x_label_formatter: Some(&si_hz),
+                y_label_formatter: Some(&db),
+                ..SpectrumChartConfig::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_visualize_spectrum_plotters_features() {
+        // two peaks; the centroid lies between them
+        let spectrum = (0..5000)
+            .step_by(10)
+            .map(|f| {
+                let peak = |center: f32| 1.0 / (1.0 + ((f as f32 - center) / 50.0).powi(2));
+                (f, peak(440.0) + 0.5 * peak(2500.0))
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        spectrum_static_plotters_png_visualize_with_config(
+            &spectrum,
+            TEST_OUT_DIR,
+            "spectrum_two_peaks_plotters_visualization_features.png",
+            &SpectrumChartConfig {
+                show_features: true,
+                normalize: SpectrumNormalize::UnitArea,
+                title: Some("Two peaks (normalized to unit area)"),
+                ..SpectrumChartConfig::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_visualize_spectrum_plotters_colored_mesh() {
+        let spectrum = (0..200)
+            .step_by(5)
+            .map(|f| (f, 100.0 / (1.0 + (f as f32 - 60.0).powi(2) / 50.0)))
+            .collect::<BTreeMap<_, _>>();
+
+        for (mesh, filename) in [
+            (
+                MeshMode::Colored(BLUE),
+                "spectrum_plotters_mesh_colored.png",
+            ),
+            (MeshMode::None, "spectrum_plotters_mesh_none.png"),
+        ] {
+            spectrum_static_plotters_png_visualize_with_config(
+                &spectrum,
+                TEST_OUT_DIR,
+                filename,
+                &SpectrumChartConfig {
+                    mesh,
+                    ..SpectrumChartConfig::default()
+                },
+            );
+        }
+    }
+
+    #[test]
+    fn test_visualize_spectrum_plotters_custom_font() {
+        let spectrum = (0..200)
+            .step_by(5)
+            .map(|f| (f, 100.0 / (1.0 + (f as f32 - 60.0).powi(2) / 50.0)))
+            .collect::<BTreeMap<_, _>>();
+
+        spectrum_static_plotters_png_visualize_with_config(
+            &spectrum,
+            TEST_OUT_DIR,
+            "spectrum_plotters_monospace_font.png",
+            &SpectrumChartConfig {
+                font: Some("monospace".to_string()),
+                show_features: true,
+                ..SpectrumChartConfig::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_visualize_spectrum_plotters_caption_color() {
+        let spectrum = (0..200)
+            .step_by(5)
+            .map(|f| (f, 100.0 / (1.0 + (f as f32 - 60.0).powi(2) / 50.0)))
+            .collect::<BTreeMap<_, _>>();
+
+        spectrum_static_plotters_png_visualize_with_config(
+            &spectrum,
+            TEST_OUT_DIR,
+            "spectrum_plotters_blue_caption.png",
+            &SpectrumChartConfig {
+                title: Some("60 Hz peak"),
+                caption_color: Some(BLUE),
+                ..SpectrumChartConfig::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_visualize_spectrum_plotters_smoothed() {
+        // noisy spectrum with a peak at 60 Hz
+        let spectrum = (0..400)
+            .map(|f| {
+                let noise = if f % 3 == 0 { 30.0 } else { 0.0 };
+                (f, noise + 100.0 / (1.0 + (f as f32 - 60.0).powi(2) / 200.0))
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        spectrum_static_plotters_png_visualize_with_config(
+            &spectrum,
+            TEST_OUT_DIR,
+            "spectrum_plotters_smoothed.png",
+            &SpectrumChartConfig {
+                smooth_window: Some(9),
+                ..SpectrumChartConfig::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_visualize_spectrum_plotters_f64() {
+        // high dynamic range: the magnitudes span 60 orders of magnitude
+        let spectrum = (0..200)
+            .step_by(10)
+            .map(|f| (f, if f == 60 { 1e30 } else { 1e-30 * f as f64 }))
+            .collect::<BTreeMap<_, _>>();
+
+        spectrum_static_plotters_png_visualize_f64(
+            &spectrum,
+            TEST_OUT_DIR,
+            "spectrum_60hz_peak_plotters_visualization_f64.png",
+            &SpectrumChartConfig {
+                normalize: SpectrumNormalize::Max,
+                ..SpectrumChartConfig::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_render_spectrum_to_buffer() {
+        let mut spectrum = BTreeMap::new();
+        spectrum.insert(0, 0.0);
+        spectrum.insert(50, 120.0);
+        spectrum.insert(60, 140.0);
+        spectrum.insert(70, 120.0);
+        spectrum.insert(130, 0.0);
+
+        let rgb_buf = render_spectrum_to_buffer(&spectrum, 400, 300);
+        assert_eq!(rgb_buf.len(), 400 * 300 * 3);
+        // background is white; the chart must have drawn something else too
+        assert!(rgb_buf.iter().any(|x| *x != 255));
+
+        // the y-axis is labeled with the normalized magnitudes
+        let normalized_rgb_buf = render_spectrum_to_buffer_with_config(
+            &spectrum,
+            400,
+            300,
+            &SpectrumChartConfig {
+                normalize: SpectrumNormalize::Max,
+                ..SpectrumChartConfig::default()
+            },
+        );
+        assert_eq!(normalized_rgb_buf.len(), rgb_buf.len());
+        assert_ne!(normalized_rgb_buf, rgb_buf);
+    }
+
+    #[test]
+    fn test_render_spectrum_to_buffer_empty_and_one_bin() {
+        let rgb_buf = render_spectrum_to_buffer(&BTreeMap::new(), 400, 300);
+        assert_eq!(rgb_buf.len(), 400 * 300 * 3);
+        // the axes are drawn anyway
+        assert!(rgb_buf.iter().any(|x| *x != 255));
+
+        for frequency in [0, 100] {
+            let spectrum = std::iter::once((frequency, 1.0)).collect::<BTreeMap<_, _>>();
+            let rgb_buf = render_spectrum_to_buffer(&spectrum, 400, 300);
+            assert_eq!(rgb_buf.len(), 400 * 300 * 3);
+        }
+    }
+
+    #[test]
+    fn test_visualize_symmetric_spectrum_plotters() {
+        // conjugate-symmetric spectrum of a real signal with peaks at +-60 Hz
+        let mut spectrum = BTreeMap::new();
+        for (frequency, magnitude) in [(0, 10.0), (20, 5.0), (40, 30.0), (60, 140.0), (80, 30.0)] {
+            spectrum.insert(frequency, magnitude);
+            spectrum.insert(-frequency, magnitude);
+        }
+
+        spectrum_static_plotters_png_visualize_symmetric(
+            &spectrum,
+            TEST_OUT_DIR,
+            "spectrum_60hz_peak_plotters_visualization_symmetric.png",
+        );
+        spectrum_static_plotters_png_visualize_symmetric_with_config(
+            &spectrum,
+            TEST_OUT_DIR,
+            "spectrum_60hz_peak_plotters_visualization_symmetric_unit_area.png",
+            &SpectrumChartConfig {
+                normalize: SpectrumNormalize::UnitArea,
+                ..SpectrumChartConfig::default()
+            },
+        );
+    }
+
+    /// This test works, if it doesn't panic.
+    #[test]
+    fn test_visualize_silence_spectrum_plotters() {
+        let spectrum = (0..200).step_by(10).map(|f| (f, 0.0)).collect();
+        spectrum_static_plotters_png_visualize_with_config(
+            &spectrum,
+            TEST_OUT_DIR,
+            "spectrum_silence_plotters_visualization.png",
+            &SpectrumChartConfig {
+                show_features: true,
+                normalize: SpectrumNormalize::Max,
+                ..SpectrumChartConfig::default()
+            },
+        );
+    }
+
     #[allow(non_snake_case)]
     #[test]
     #[should_panic]
@@ -1,14 +1,69 @@
 //! Static spectrum analysis: print spectrum to PNG file.
 
-use crate::util::png::write_png_file_rgb_tuples;
+use crate::spectrum::normalize::{normalize_spectrum, normalize_spectrum_f64, SpectrumNormalize};
+use crate::spectrum::smooth::{smooth_spectrum, smooth_spectrum_f64};
+use crate::util::png::{write_png_file_rgb_tuples_with_options, PngOptions};
 use std::collections::BTreeMap;
 use std::path::PathBuf;
 
+/// Visualizes a frequency spectrum as bars in a PNG file.
+///
+/// `highlights` maps frequencies to colors. Bars within 5 Hz of a highlighted frequency
+/// are drawn wider and in the color of the first matching entry, all other bars are black.
+///
+/// The x position of each bar is proportional to its frequency; the x-axis spans from the
+/// lowest to the highest frequency of the spectrum.
+///
+/// The bars are scaled to the peak of the spectrum, i.e. like [`SpectrumNormalize::Max`].
+/// See [`SpectrumPngConfig::normalize`] for alternatives.
 pub fn spectrum_static_png_visualize(
     frequency_spectrum: &BTreeMap<u32, f32>,
     directory: &str,
     filename: &str,
-    highlighted_frequencies: &[f32],
+    highlights: &[(f32, (u8, u8, u8))],
+) {
+    spectrum_static_png_visualize_with_config(
+        frequency_spectrum,
+        directory,
+        filename,
+        highlights,
+        &SpectrumPngConfig::default(),
+    )
+}
+
+/// Additional configuration of [`spectrum_static_png_visualize_with_config`].
+#[derive(Debug, Copy, Clone, Default)]
+pub struct SpectrumPngConfig {
+    /// Each bar fills the gap up to the bar of the next frequency (minus one pixel of
+    /// space), so that sparse spectra read as bar chart instead of thin spikes. The bar of
+    /// the highest frequency stays one pixel wide. Default is false, i.e. all bars are one
+    /// pixel wide.
+    pub proportional_width: bool,
+    /// How the magnitudes are normalized before they are drawn. The bars are scaled to the
+    /// peak of the normalized spectrum, unless [`Self::y_max`] is set. Default is
+    /// [`SpectrumNormalize::None`].
+    pub normalize: SpectrumNormalize,
+    /// Fixed magnitude at the top edge of the image, e.g. 1.0 with
+    /// [`SpectrumNormalize::Max`], so that images of different spectra are comparable.
+    /// Higher bars are clipped. Default is `None`, i.e. the bars are scaled to the peak.
+    pub y_max: Option<f32>,
+    /// Smooths the magnitudes with a moving average over the given number of frequency
+    /// bins before they are normalized and drawn. See [`smooth_spectrum`]. Default is
+    /// `None`, i.e. the raw spectrum.
+    pub smooth_window: Option<usize>,
+    /// Options of the PNG encoding, e.g. the resolution for print or the compression. See
+    /// [`PngOptions`].
+    pub png: PngOptions,
+}
+
+/// Like [`spectrum_static_png_visualize`] but with additional configuration, e.g. bars
+/// with a width proportional to the spacing of the frequencies. See [`SpectrumPngConfig`].
+pub fn spectrum_static_png_visualize_with_config(
+    frequency_spectrum: &BTreeMap<u32, f32>,
+    directory: &str,
+    filename: &str,
+    highlights: &[(f32, (u8, u8, u8))],
+    cfg: &SpectrumPngConfig,
 ) {
     // assert no NAN
     assert!(
@@ -16,48 +71,142 @@ pub fn spectrum_static_png_visualize(
         "There are NAN-values in the spectrum!"
     );
 
+    // a window of one bin keeps the spectrum unchanged
+    let frequency_spectrum = &smooth_spectrum(frequency_spectrum, cfg.smooth_window.unwrap_or(1));
+    let frequency_spectrum = &normalize_spectrum(frequency_spectrum, cfg.normalize);
+
     let image_width = 5000;
     let image_height = 3000;
 
     let mut rgb_img = vec![vec![(255, 255, 255); image_width]; image_height];
 
-    // find maximum for graphics scaling
-    let mut max = 0.0;
-    for mag in frequency_spectrum.values() {
-        if *mag > max {
-            max = *mag;
-        }
-    }
+    // find maximum for graphics scaling, unless the scale is fixed
+    let max = cfg.y_max.map_or_else(
+        || frequency_spectrum.values().copied().fold(0.0, f32::max),
+        |y_max| {
+            assert!(y_max > 0.0, "y_max must be positive!");
+            y_max
+        },
+    );
 
-    let x_step = image_width as f64 / frequency_spectrum.len() as f64;
-    for (i, (frequency, mag)) in frequency_spectrum.iter().enumerate() {
-        let mag = mag / max * image_height as f32;
+    // silence: all bars are at zero; draw a flat baseline instead of dividing by zero
+    if max == 0.0 {
+        rgb_img[image_height - 1]
+            .iter_mut()
+            .for_each(|px| *px = (0, 0, 0));
+    }
 
-        let x = (i as f64 * x_step) as usize;
+    // the x-axis is linear in frequency, also for non-uniformly spaced frequencies
+    let min_frequency = frequency_spectrum.keys().next().copied().unwrap_or(0);
+    let max_frequency = frequency_spectrum.keys().last().copied().unwrap_or(0);
+    let x_per_hz = if max_frequency > min_frequency {
+        (image_width - 1) as f64 / (max_frequency - min_frequency) as f64
+    } else {
+        0.0
+    };
+    let to_x = |frequency: u32| ((frequency - min_frequency) as f64 * x_per_hz).round() as usize;
+    let next_frequencies = frequency_spectrum
+        .keys()
+        .skip(1)
+        .copied()
+        .map(Some)
+        .chain(std::iter::once(None));
+    for ((frequency, mag), next_frequency) in frequency_spectrum.iter().zip(next_frequencies) {
+        let mag = if max == 0.0 {
+            0.0
+        } else {
+            (mag / max * image_height as f32).min(image_height as f32)
+        };
 
-        for j in 0..mag as usize {
-            let mut color = (0, 0, 0);
+        let x = to_x(*frequency);
+        // last column of the bar; one pixel of space to the next bar
+        let x_end = next_frequency
+            .filter(|_| cfg.proportional_width)
+            .map_or(x, |next| to_x(next).saturating_sub(1).max(x));
 
-            let highlight = highlighted_frequencies
-                .iter()
-                .any(|f| (*frequency as f32 - *f).abs() < 5.0);
-            if highlight {
-                color = (255, 0, 0);
-            }
+        let highlight_color = highlights
+            .iter()
+            .find(|(f, _)| (*frequency as f32 - *f).abs() < 5.0)
+            .map(|(_, color)| *color);
+        let highlight = highlight_color.is_some();
+        let color = highlight_color.unwrap_or((0, 0, 0));
 
+        for j in 0..mag as usize {
             // make it wider
             if x > 2 && highlight {
                 rgb_img[image_height - 1 - j][x - 1] = color;
                 rgb_img[image_height - 1 - j][x - 2] = color;
             }
-            rgb_img[image_height - 1 - j][x] = color;
+            rgb_img[image_height - 1 - j][x..=x_end]
+                .iter_mut()
+                .for_each(|px| *px = color);
         }
     }
 
     let mut path = PathBuf::new();
     path.push(directory);
     path.push(filename);
-    write_png_file_rgb_tuples(&path, &rgb_img);
+    write_png_file_rgb_tuples_with_options(&path, &rgb_img, &cfg.png);
+}
+
+/// Like [`spectrum_static_png_visualize`] but for `f64` magnitudes.
+///
+/// The magnitudes are scaled to the peak with `f64`, so that spectra with a high
+/// dynamic range don't lose precision before they are drawn.
+pub fn spectrum_static_png_visualize_f64(
+    frequency_spectrum: &BTreeMap<u32, f64>,
+    directory: &str,
+    filename: &str,
+    highlights: &[(f32, (u8, u8, u8))],
+) {
+    spectrum_static_png_visualize_f64_with_config(
+        frequency_spectrum,
+        directory,
+        filename,
+        highlights,
+        &SpectrumPngConfig::default(),
+    )
+}
+
+/// Like [`spectrum_static_png_visualize_f64`] but with additional configuration. See
+/// [`SpectrumPngConfig`].
+///
+/// The smoothing and the normalization are done with `f64`.
+pub fn spectrum_static_png_visualize_f64_with_config(
+    frequency_spectrum: &BTreeMap<u32, f64>,
+    directory: &str,
+    filename: &str,
+    highlights: &[(f32, (u8, u8, u8))],
+    cfg: &SpectrumPngConfig,
+) {
+    // assert no NAN
+    assert!(
+        !frequency_spectrum.iter().any(|(_, f)| f.is_nan()),
+        "There are NAN-values in the spectrum!"
+    );
+    // a window of one bin keeps the spectrum unchanged
+    let frequency_spectrum =
+        &smooth_spectrum_f64(frequency_spectrum, cfg.smooth_window.unwrap_or(1));
+    // without normalization and a fixed scale, the bars are scaled to the peak anyway
+    let normalize = match (cfg.normalize, cfg.y_max) {
+        (SpectrumNormalize::None, None) => SpectrumNormalize::Max,
+        (normalize, _) => normalize,
+    };
+    let frequency_spectrum = normalize_spectrum_f64(frequency_spectrum, normalize)
+        .into_iter()
+        .map(|(frequency, magnitude)| (frequency, magnitude as f32))
+        .collect();
+    // normalizing the normalized spectrum again doesn't change it
+    spectrum_static_png_visualize_with_config(
+        &frequency_spectrum,
+        directory,
+        filename,
+        highlights,
+        &SpectrumPngConfig {
+            smooth_window: None,
+            ..*cfg
+        },
+    )
 }
 
 #[cfg(test)]
@@ -90,7 +239,220 @@ mod tests {
             &spectrum,
             TEST_OUT_DIR,
             "spectrum_60hz_peak_basic_visualization.png",
-            &[60.0],
+            &[(60.0, (255, 0, 0))],
+        );
+    }
+
+    #[test]
+    fn test_visualize_spectrum_multiple_highlight_colors() {
+        let spectrum = (0..200)
+            .step_by(5)
+            .map(|f| {
+                let peak = |center: f32| 100.0 / (1.0 + (f as f32 - center).powi(2) / 50.0);
+                (f, peak(50.0) + peak(150.0) * 0.6)
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        spectrum_static_png_visualize(
+            &spectrum,
+            TEST_OUT_DIR,
+            "spectrum_two_peaks_highlight_colors.png",
+            &[(50.0, (255, 0, 0)), (150.0, (0, 0, 255))],
+        );
+    }
+
+    #[test]
+    fn test_visualize_spectrum_f64() {
+        // the peak exceeds the range of f32
+        let spectrum = (0..200)
+            .step_by(5)
+            .map(|f| (f, 1e40 / (1.0 + (f as f64 - 60.0).powi(2) / 50.0)))
+            .collect::<BTreeMap<_, _>>();
+
+        spectrum_static_png_visualize_f64(
+            &spectrum,
+            TEST_OUT_DIR,
+            "spectrum_60hz_peak_basic_visualization_f64.png",
+            &[(60.0, (255, 0, 0))],
+        );
+    }
+
+    #[test]
+    fn test_visualize_spectrum_smooth_window() {
+        let spectrum = (0..200)
+            .step_by(5)
+            .map(|f| (f, if f % 10 == 0 { 1.0 } else { 3.0 }))
+            .collect::<BTreeMap<u32, f32>>();
+        let cfg = SpectrumPngConfig {
+            smooth_window: Some(3),
+            ..SpectrumPngConfig::default()
+        };
+
+        spectrum_static_png_visualize_with_config(
+            &spectrum,
+            TEST_OUT_DIR,
+            "spectrum_smooth_window.png",
+            &[],
+            &cfg,
+        );
+        spectrum_static_png_visualize(
+            &smooth_spectrum(&spectrum, 3),
+            TEST_OUT_DIR,
+            "spectrum_smooth_window_expected.png",
+            &[],
+        );
+        let read = |filename| std::fs::read(PathBuf::from(TEST_OUT_DIR).join(filename)).unwrap();
+        assert_eq!(
+            read("spectrum_smooth_window.png"),
+            read("spectrum_smooth_window_expected.png")
+        );
+
+        // the smoothing of f64 magnitudes beyond the range of f32 doesn't overflow
+        let spectrum = spectrum
+            .iter()
+            .map(|(f, mag)| (*f, *mag as f64 * 1e40))
+            .collect::<BTreeMap<_, _>>();
+        spectrum_static_png_visualize_f64_with_config(
+            &spectrum,
+            TEST_OUT_DIR,
+            "spectrum_smooth_window_f64.png",
+            &[],
+            &cfg,
+        );
+        assert_eq!(
+            read("spectrum_smooth_window_f64.png"),
+            read("spectrum_smooth_window_expected.png")
+        );
+    }
+
+    #[test]
+    fn test_visualize_sparse_spectrum() {
+        // dense below 100 Hz, sparse above; the bars must not be evenly spaced
+        let spectrum = (0..100)
+            .step_by(5)
+            .chain([200, 400, 800, 1600])
+            .map(|f| (f, 100.0 / (1.0 + (f as f32 - 50.0).powi(2) / 50.0) + 10.0))
+            .collect::<BTreeMap<_, _>>();
+
+        spectrum_static_png_visualize(
+            &spectrum,
+            TEST_OUT_DIR,
+            "spectrum_sparse_basic_visualization.png",
+            &[(800.0, (255, 0, 0))],
+        );
+    }
+
+    #[test]
+    fn test_visualize_sparse_spectrum_proportional_width() {
+        let spectrum = [
+            (0, 10.0),
+            (100, 40.0),
+            (200, 100.0),
+            (400, 60.0),
+            (800, 20.0),
+        ]
+        .iter()
+        .copied()
+        .collect::<BTreeMap<u32, f32>>();
+
+        spectrum_static_png_visualize_with_config(
+            &spectrum,
+            TEST_OUT_DIR,
+            "spectrum_sparse_proportional_width.png",
+            &[(200.0, (255, 0, 0))],
+            &SpectrumPngConfig {
+                proportional_width: true,
+                ..SpectrumPngConfig::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_visualize_spectrum_normalize_and_y_max() {
+        let spectrum = [(0, 1.0), (100, 2.0), (200, 3.0), (300, 4.0)]
+            .iter()
+            .copied()
+            .collect::<BTreeMap<u32, f32>>();
+        let read = |filename: &str, cfg: SpectrumPngConfig| {
+            spectrum_static_png_visualize_with_config(
+                &spectrum,
+                TEST_OUT_DIR,
+                filename,
+                &[],
+                &SpectrumPngConfig {
+                    proportional_width: true,
+                    ..cfg
+                },
+            );
+            std::fs::read(PathBuf::from(TEST_OUT_DIR).join(filename)).unwrap()
+        };
+
+        let raw = read("spectrum_normalize_none.png", SpectrumPngConfig::default());
+        // the bars are scaled to the peak of the normalized spectrum, like without normalization
+        for (normalize, filename) in [
+            (SpectrumNormalize::Median, "spectrum_normalize_median.png"),
+            (
+                SpectrumNormalize::UnitArea,
+                "spectrum_normalize_unit_area.png",
+            ),
+        ] {
+            let cfg = SpectrumPngConfig {
+                normalize,
+                ..SpectrumPngConfig::default()
+            };
+            assert_eq!(read(filename, cfg), raw);
+        }
+
+        // a fixed scale: the peak of 0.4 reaches 40% of the height
+        let fixed = read(
+            "spectrum_normalize_unit_area_y_max.png",
+            SpectrumPngConfig {
+                normalize: SpectrumNormalize::UnitArea,
+                y_max: Some(1.0),
+                ..SpectrumPngConfig::default()
+            },
+        );
+        assert_ne!(fixed, raw);
+    }
+
+    #[test]
+    fn test_visualize_spectrum_dpi() {
+        let spectrum = [(0, 1.0), (100, 2.0)]
+            .iter()
+            .copied()
+            .collect::<BTreeMap<u32, f32>>();
+
+        spectrum_static_png_visualize_with_config(
+            &spectrum,
+            TEST_OUT_DIR,
+            "spectrum_dpi.png",
+            &[],
+            &SpectrumPngConfig {
+                png: PngOptions {
+                    dpi: Some(300),
+                    ..PngOptions::default()
+                },
+                ..SpectrumPngConfig::default()
+            },
+        );
+
+        let file =
+            std::fs::File::open(PathBuf::from(TEST_OUT_DIR).join("spectrum_dpi.png")).unwrap();
+        let reader = png::Decoder::new(file).read_info().unwrap();
+        let pixel_dims = reader.info().pixel_dims.unwrap();
+        // 300 dpi = 11811 pixels per meter
+        assert_eq!((pixel_dims.xppu, pixel_dims.yppu), (11811, 11811));
+    }
+
+    /// This test works, if it doesn't panic.
+    #[test]
+    fn test_visualize_silence_spectrum() {
+        let spectrum = (0..200).step_by(10).map(|f| (f, 0.0)).collect();
+        spectrum_static_png_visualize(
+            &spectrum,
+            TEST_OUT_DIR,
+            "spectrum_silence_basic_visualization.png",
+            &[],
         );
     }
 
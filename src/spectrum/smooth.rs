@@ -0,0 +1,108 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Spectral smoothing of a single spectrum, e.g. to make noisy spectra more readable in a
+//! report. For smoothing over consecutive frames of a live spectrum, see
+//! [`crate::dynamic::smoothing`].
+
+use std::collections::BTreeMap;
+use std::iter::Sum;
+use std::ops::Div;
+
+/// Smooths the magnitudes with a centered moving average over `window` bins. At the
+/// borders of the spectrum, the window is shortened to the existing bins.
+///
+/// The frequencies are unchanged. A window of 1 returns the spectrum unchanged.
+pub fn smooth_spectrum(
+    frequency_spectrum: &BTreeMap<u32, f32>,
+    window: usize,
+) -> BTreeMap<u32, f32> {
+    moving_average(frequency_spectrum, window)
+}
+
+/// Like [`smooth_spectrum`] but for `f64` magnitudes.
+pub fn smooth_spectrum_f64(
+    frequency_spectrum: &BTreeMap<u32, f64>,
+    window: usize,
+) -> BTreeMap<u32, f64> {
+    moving_average(frequency_spectrum, window)
+}
+
+/// Implementation of [`smooth_spectrum`] for `f32` and `f64` magnitudes.
+fn moving_average<T>(frequency_spectrum: &BTreeMap<u32, T>, window: usize) -> BTreeMap<u32, T>
+where
+    T: Copy + From<f32> + Div<Output = T> + Sum,
+{
+    assert!(window > 0, "window must be positive!");
+    let magnitudes = frequency_spectrum.values().copied().collect::<Vec<_>>();
+    // bins before and after the current bin; an even window reaches one bin further back
+    let before = window / 2;
+    let after = (window - 1) / 2;
+    frequency_spectrum
+        .keys()
+        .enumerate()
+        .map(|(i, frequency)| {
+            let begin = i.saturating_sub(before);
+            let end = (i + after + 1).min(magnitudes.len());
+            let bins = &magnitudes[begin..end];
+            let average = bins.iter().copied().sum::<T>() / T::from(bins.len() as f32);
+            (*frequency, average)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smooth_spectrum() {
+        let spectrum = [(10, 0.0), (20, 3.0), (30, 0.0), (40, 3.0), (50, 0.0)]
+            .into_iter()
+            .collect::<BTreeMap<_, _>>();
+        assert_eq!(smooth_spectrum(&spectrum, 1), spectrum);
+        assert_eq!(
+            smooth_spectrum(&spectrum, 3)
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![(10, 1.5), (20, 1.0), (30, 2.0), (40, 1.0), (50, 1.5)]
+        );
+        // even window: one bin further back than forward
+        assert_eq!(
+            smooth_spectrum(&spectrum, 2)
+                .values()
+                .copied()
+                .collect::<Vec<_>>(),
+            vec![0.0, 1.5, 1.5, 1.5, 1.5]
+        );
+        assert!(smooth_spectrum(&BTreeMap::new(), 5).is_empty());
+    }
+
+    #[test]
+    fn test_smooth_spectrum_f64() {
+        let spectrum = [(0, 1e300), (1, 0.0)]
+            .into_iter()
+            .collect::<BTreeMap<_, _>>();
+        assert_eq!(smooth_spectrum_f64(&spectrum, 2)[&1], 0.5e300);
+    }
+}
@@ -0,0 +1,263 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Static spectrogram: the frequency spectrum over time as heatmap in a PNG file.
+
+use crate::util::colormap::ColorMap;
+use crate::util::png::{write_png_file_rgb_tuples_with_options, PngOptions};
+use spectrum_analyzer::scaling::divide_by_N;
+use spectrum_analyzer::windows::hann_window;
+use spectrum_analyzer::{samples_fft_to_spectrum, FrequencyLimit};
+use std::path::PathBuf;
+
+/// Magnitudes this far below the maximum magnitude (in dB) get the lowest color.
+const DYNAMIC_RANGE_DB: f32 = 80.0;
+
+/// Calculates the spectrogram of mono audio data, i.e. the frequency spectrum over time.
+///
+/// The audio data is split into frames of `fft_size` samples. The start of each frame is
+/// `hop_size` samples after the start of the previous frame, i.e. the frames overlap if
+/// `hop_size < fft_size`. Incomplete frames at the end are dropped. If there are less than
+/// `fft_size` samples, there is one zero-padded frame.
+///
+/// Returns the magnitudes as matrix (one row per time frame, one column per frequency bin)
+/// and the frequency (in Hertz) of each bin.
+///
+/// # Parameters
+/// - `samples` Mono audio samples in interval `[-1.0; 1.0]`.
+/// - `sample_rate` Sampling rate of the audio data, e.g. 44100.
+/// - `fft_size` Number of samples per FFT. Must be a power of two.
+/// - `hop_size` Number of samples between the starts of two frames. Must be positive.
+pub fn compute_spectrogram(
+    samples: &[f32],
+    sample_rate: u32,
+    fft_size: usize,
+    hop_size: usize,
+) -> (Vec<Vec<f32>>, Vec<f32>) {
+    assert!(
+        fft_size.is_power_of_two(),
+        "fft_size must be a power of two!"
+    );
+    assert!(hop_size > 0, "hop_size must be positive!");
+
+    let mut frames = (0..samples.len().saturating_sub(fft_size - 1))
+        .step_by(hop_size)
+        .map(|begin| &samples[begin..begin + fft_size])
+        .collect::<Vec<_>>();
+    // zero-padded fallback for very short audio data
+    let mut padded = vec![];
+    if frames.is_empty() {
+        padded.extend_from_slice(samples);
+        padded.resize(fft_size, 0.0);
+        frames.push(&padded);
+    }
+
+    let mut frequencies = Vec::new();
+    let magnitudes = frames
+        .iter()
+        .map(|frame| {
+            let spectrum = samples_fft_to_spectrum(
+                &hann_window(frame),
+                sample_rate,
+                FrequencyLimit::All,
+                Some(&divide_by_N),
+            )
+            .unwrap();
+            if frequencies.is_empty() {
+                frequencies = spectrum.data().iter().map(|(fr, _)| fr.val()).collect();
+            }
+            spectrum
+                .data()
+                .iter()
+                .map(|(_, magnitude)| magnitude.val())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    (magnitudes, frequencies)
+}
+
+/// Visualizes the spectrogram of mono audio data in a PNG file.
+///
+/// The audio data is split into frames of `fft_size` samples that start `hop_size` samples
+/// apart. Each frame becomes one column of the image. Overlapping frames, e.g. with
+/// `hop_size = fft_size / 2`, improve the temporal resolution. See [`compute_spectrogram`]
+/// and [`spectrogram_matrix_png_visualize`]. To store a resolution in the file, use
+/// [`spectrogram_matrix_png_visualize_with_options`].
+///
+/// # Parameters
+/// - `samples` Mono audio samples in interval `[-1.0; 1.0]`.
+/// - `sample_rate` Sampling rate of the audio data, e.g. 44100.
+/// - `fft_size` Number of samples per FFT. Must be a power of two.
+/// - `hop_size` Number of samples between the starts of two frames. Must be positive.
+/// - `color_map` Colormap for the magnitudes. See [`ColorMap`].
+/// - `directory` Directory of the PNG file.
+/// - `filename` Name of the PNG file.
+pub fn spectrogram_png_visualize(
+    samples: &[f32],
+    sample_rate: u32,
+    fft_size: usize,
+    hop_size: usize,
+    color_map: ColorMap,
+    directory: &str,
+    filename: &str,
+) {
+    let (spectrogram, _) = compute_spectrogram(samples, sample_rate, fft_size, hop_size);
+    spectrogram_matrix_png_visualize(&spectrogram, color_map, directory, filename);
+}
+
+/// Visualizes a precomputed spectrogram in a PNG file, e.g. from [`compute_spectrogram`].
+///
+/// Each row of `spectrogram` (a time frame) becomes one column of the image, each
+/// frequency bin one row (low frequencies at the bottom). The magnitudes are mapped to
+/// colors in dB relative to the maximum magnitude, with a dynamic range of 80 dB.
+pub fn spectrogram_matrix_png_visualize(
+    spectrogram: &[Vec<f32>],
+    color_map: ColorMap,
+    directory: &str,
+    filename: &str,
+) {
+    spectrogram_matrix_png_visualize_with_options(
+        spectrogram,
+        color_map,
+        directory,
+        filename,
+        &PngOptions::default(),
+    )
+}
+
+/// Like [`spectrogram_matrix_png_visualize`] but with the given [`PngOptions`], e.g. a
+/// resolution.
+pub fn spectrogram_matrix_png_visualize_with_options(
+    spectrogram: &[Vec<f32>],
+    color_map: ColorMap,
+    directory: &str,
+    filename: &str,
+    png: &PngOptions,
+) {
+    let image = spectrogram_to_image(spectrogram, color_map);
+
+    let mut path = PathBuf::new();
+    path.push(directory);
+    path.push(filename);
+    write_png_file_rgb_tuples_with_options(&path, &image, png);
+}
+
+/// Maps the spectrogram to RGB image data. See [`spectrogram_matrix_png_visualize`].
+fn spectrogram_to_image(spectrogram: &[Vec<f32>], color_map: ColorMap) -> Vec<Vec<(u8, u8, u8)>> {
+    assert!(!spectrogram.is_empty(), "There is no audio data!");
+    assert!(
+        !spectrogram.iter().flatten().any(|x| x.is_nan()),
+        "There are NAN-values in the spectrogram!"
+    );
+
+    let max = spectrogram.iter().flatten().copied().fold(0.0, f32::max);
+    let bin_count = spectrogram[0].len();
+
+    // RGB image data
+    let mut image = vec![vec![(0, 0, 0); spectrogram.len()]; bin_count];
+    for (x, frame) in spectrogram.iter().enumerate() {
+        for (bin, magnitude) in frame.iter().enumerate() {
+            let value = if max == 0.0 {
+                0.0
+            } else {
+                let db = 20.0 * (magnitude / max).log10();
+                (db + DYNAMIC_RANGE_DB) / DYNAMIC_RANGE_DB
+            };
+            // low frequencies at the bottom
+            image[bin_count - 1 - bin][x] = color_map.map(value);
+        }
+    }
+    image
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::testutil::TEST_OUT_DIR;
+
+    #[test]
+    fn test_spectrogram_png_visualize_chirp() {
+        // linear chirp from 0 Hz to 10 kHz in 2 seconds
+        let sample_rate = 44100;
+        let samples = (0..sample_rate * 2)
+            .map(|i| i as f32 / sample_rate as f32)
+            .map(|t| (2.0 * std::f32::consts::PI * 2500.0 * t * t).sin())
+            .collect::<Vec<_>>();
+
+        for (color_map, filename) in [
+            (ColorMap::Viridis, "spectrogram_chirp_viridis.png"),
+            (ColorMap::Magma, "spectrogram_chirp_magma.png"),
+            (ColorMap::Grayscale, "spectrogram_chirp_grayscale.png"),
+            (ColorMap::Jet, "spectrogram_chirp_jet.png"),
+        ] {
+            spectrogram_png_visualize(
+                &samples,
+                sample_rate,
+                512,
+                256,
+                color_map,
+                TEST_OUT_DIR,
+                filename,
+            );
+        }
+    }
+
+    #[test]
+    fn test_compute_spectrogram_shape_and_peak() {
+        // 1000 Hz sine for one second
+        let sample_rate = 44100;
+        let samples = (0..sample_rate)
+            .map(|i| i as f32 / sample_rate as f32)
+            .map(|t| (2.0 * std::f32::consts::PI * 1000.0 * t).sin())
+            .collect::<Vec<_>>();
+
+        let (spectrogram, frequencies) = compute_spectrogram(&samples, sample_rate, 1024, 512);
+        assert_eq!(spectrogram.len(), (44100 - 1024) / 512 + 1);
+        assert_eq!(frequencies.len(), 1024 / 2 + 1);
+        for frame in &spectrogram {
+            assert_eq!(frame.len(), frequencies.len());
+            let (peak_bin, _) = frame
+                .iter()
+                .enumerate()
+                .max_by(|(_, m1), (_, m2)| m1.partial_cmp(m2).unwrap())
+                .unwrap();
+            assert!((frequencies[peak_bin] - 1000.0).abs() < 50.0);
+        }
+
+        // short audio data is zero-padded
+        let (spectrogram, _) = compute_spectrogram(&samples[..100], sample_rate, 1024, 512);
+        assert_eq!(spectrogram.len(), 1);
+    }
+
+    #[test]
+    fn test_spectrogram_to_image_low_frequencies_at_bottom() {
+        let spectrogram = vec![vec![1.0, 0.0, 0.0], vec![0.0, 0.0, 1.0]];
+        let image = spectrogram_to_image(&spectrogram, ColorMap::Grayscale);
+        assert_eq!(image.len(), 3);
+        assert_eq!(image[0].len(), 2);
+        assert_eq!(image[2][0], (255, 255, 255));
+        assert_eq!(image[0][1], (255, 255, 255));
+        assert_eq!(image[0][0], (0, 0, 0));
+    }
+}
@@ -0,0 +1,110 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use crate::{ChannelInterleavement, Channels};
+
+#[test]
+fn to_multichannel_data_lrlr() {
+    let interleaved = [1, 2, 3, 1, 2, 3, 1, 2, 3, 1, 2, 3];
+    let channels = ChannelInterleavement::LRLR.to_multichannel_data(&interleaved, 3);
+    assert_eq!(
+        channels,
+        vec![vec![1, 1, 1, 1], vec![2, 2, 2, 2], vec![3, 3, 3, 3]]
+    );
+}
+
+#[test]
+fn to_multichannel_data_llrr() {
+    let interleaved = [1, 1, 2, 2, 3, 3];
+    let channels = ChannelInterleavement::LLRR.to_multichannel_data(&interleaved, 3);
+    assert_eq!(channels, vec![vec![1, 1], vec![2, 2], vec![3, 3]]);
+}
+
+#[test]
+fn to_multichannel_data_empty() {
+    for interleavement in [ChannelInterleavement::LRLR, ChannelInterleavement::LLRR] {
+        let channels = interleavement.to_multichannel_data::<i16>(&[], 3);
+        assert_eq!(channels, vec![Vec::<i16>::new(); 3]);
+    }
+}
+
+#[test]
+fn to_multichannel_data_matches_stereo() {
+    let interleaved = [1, 2, 3, 4, 5, 6];
+    for interleavement in [ChannelInterleavement::LRLR, ChannelInterleavement::LLRR] {
+        let (left, right) = interleavement.to_channel_data(&interleaved);
+        assert_eq!(
+            interleavement.to_multichannel_data(&interleaved, 2),
+            vec![left, right]
+        );
+    }
+}
+
+#[test]
+fn from_channel_data_round_trip() {
+    let interleaved = [1_i16, -1, 2, -2, 3, -3];
+    for interleavement in [ChannelInterleavement::LRLR, ChannelInterleavement::LLRR] {
+        let (left, right) = interleavement.to_channel_data(&interleaved);
+        assert_eq!(
+            interleavement.from_channel_data(&left, &right),
+            interleaved.to_vec()
+        );
+    }
+    assert_eq!(
+        ChannelInterleavement::LRLR.from_channel_data(&[1, 2], &[3, 4]),
+        vec![1, 3, 2, 4]
+    );
+    assert_eq!(
+        ChannelInterleavement::LLRR.from_channel_data(&[1, 2], &[3, 4]),
+        vec![1, 2, 3, 4]
+    );
+}
+
+#[test]
+#[should_panic]
+fn from_channel_data_different_lengths() {
+    ChannelInterleavement::LRLR.from_channel_data(&[1, 2], &[3]);
+}
+
+#[test]
+#[should_panic]
+fn to_multichannel_data_invalid_length() {
+    ChannelInterleavement::LRLR.to_multichannel_data(&[1, 2, 3, 4, 5], 2);
+}
+
+#[test]
+fn channels_from_count() {
+    let interleavement = ChannelInterleavement::LRLR;
+    assert!(Channels::from_count(1, interleavement).is_mono());
+    assert!(Channels::from_count(2, interleavement).is_stereo());
+    assert!(Channels::from_count(6, interleavement).is_multi());
+    for count in 1..=6 {
+        assert_eq!(Channels::from_count(count, interleavement).count(), count);
+    }
+}
+
+#[test]
+#[should_panic]
+fn channels_from_count_zero() {
+    Channels::from_count(0, ChannelInterleavement::LRLR);
+}
@@ -1,5 +1,6 @@
 //! Module for all tests and "example-like" tests.
 
+pub mod channel_interleavement;
 pub mod testutil;
 pub mod visualize_sine_10hz;
 pub mod visualize_sine_50hz_plus_250hz;
@@ -39,5 +39,5 @@ fn visualize_sine_10hz() {
         Channels::Mono,
         TEST_OUT_DIR,
         "sinus-wave-10hz.png",
-    )
+    );
 }
@@ -42,5 +42,5 @@ fn visualize_sine_50hz_plus_250hz() {
         Channels::Mono,
         TEST_OUT_DIR,
         "sinus-wave-50hz_plus_250hz.png",
-    )
+    );
 }
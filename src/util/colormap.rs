@@ -0,0 +1,173 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Colormaps that map normalized values (e.g. magnitudes) to colors. Used for heatmap-like
+//! visualizations such as [`crate::spectrum::spectrogram`].
+
+/// Viridis colormap, sampled at 11 equidistant points (from matplotlib).
+const VIRIDIS: [(u8, u8, u8); 11] = [
+    (0x44, 0x01, 0x54),
+    (0x48, 0x25, 0x76),
+    (0x41, 0x44, 0x87),
+    (0x35, 0x60, 0x8d),
+    (0x2a, 0x78, 0x8e),
+    (0x21, 0x90, 0x8c),
+    (0x22, 0xa8, 0x84),
+    (0x43, 0xbf, 0x71),
+    (0x7a, 0xd1, 0x51),
+    (0xbb, 0xdf, 0x27),
+    (0xfd, 0xe7, 0x25),
+];
+
+/// Magma colormap, sampled at 11 equidistant points (from matplotlib).
+const MAGMA: [(u8, u8, u8); 11] = [
+    (0x00, 0x00, 0x04),
+    (0x14, 0x0e, 0x36),
+    (0x3b, 0x0f, 0x70),
+    (0x64, 0x1a, 0x80),
+    (0x8c, 0x29, 0x81),
+    (0xb7, 0x37, 0x79),
+    (0xde, 0x49, 0x68),
+    (0xf7, 0x70, 0x5c),
+    (0xfe, 0x9f, 0x6d),
+    (0xfe, 0xcf, 0x92),
+    (0xfc, 0xfd, 0xbf),
+];
+
+/// Standard colormaps. See [`ColorMap::map`].
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum ColorMap {
+    /// Black (0.0) to white (1.0).
+    Grayscale,
+    /// Perceptually uniform; dark blue (0.0) over green to yellow (1.0).
+    #[default]
+    Viridis,
+    /// Perceptually uniform; black (0.0) over purple and orange to light yellow (1.0).
+    Magma,
+    /// The classic rainbow colormap; dark blue (0.0) over cyan, yellow and red to dark red (1.0).
+    Jet,
+}
+
+impl ColorMap {
+    /// Maps a normalized value in interval `[0.0; 1.0]` to a color. Values outside of the
+    /// interval are clamped.
+    pub fn map(self, value: f32) -> (u8, u8, u8) {
+        self.to_fn()(value)
+    }
+
+    /// Returns the function that implements the colormap.
+    pub fn to_fn(self) -> fn(f32) -> (u8, u8, u8) {
+        match self {
+            Self::Grayscale => grayscale,
+            Self::Viridis => viridis,
+            Self::Magma => magma,
+            Self::Jet => jet,
+        }
+    }
+}
+
+/// Grayscale colormap. See [`ColorMap::Grayscale`].
+pub fn grayscale(value: f32) -> (u8, u8, u8) {
+    let gray = (clamp_unit(value) * 255.0).round() as u8;
+    (gray, gray, gray)
+}
+
+/// Viridis colormap. See [`ColorMap::Viridis`].
+pub fn viridis(value: f32) -> (u8, u8, u8) {
+    interpolate(&VIRIDIS, value)
+}
+
+/// Magma colormap. See [`ColorMap::Magma`].
+pub fn magma(value: f32) -> (u8, u8, u8) {
+    interpolate(&MAGMA, value)
+}
+
+/// Jet colormap. See [`ColorMap::Jet`].
+pub fn jet(value: f32) -> (u8, u8, u8) {
+    let value = clamp_unit(value);
+    // each channel is a trapezoid, shifted by a quarter of the interval
+    let channel = |center: f32| {
+        let intensity = (1.5 - (4.0 * value - center).abs()).clamp(0.0, 1.0);
+        (intensity * 255.0).round() as u8
+    };
+    (channel(3.0), channel(2.0), channel(1.0))
+}
+
+/// Clamps the value to interval `[0.0; 1.0]`. NaN becomes 0.0.
+// const float arithmetic requires a newer Rust version than the MSRV
+#[allow(clippy::missing_const_for_fn)]
+fn clamp_unit(value: f32) -> f32 {
+    if value.is_nan() {
+        0.0
+    } else {
+        value.clamp(0.0, 1.0)
+    }
+}
+
+/// Linear interpolation between equidistant colors of a lookup table.
+fn interpolate(table: &[(u8, u8, u8)], value: f32) -> (u8, u8, u8) {
+    let pos = clamp_unit(value) * (table.len() - 1) as f32;
+    let index = (pos as usize).min(table.len() - 2);
+    let fraction = pos - index as f32;
+    let (a, b) = (table[index], table[index + 1]);
+    let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * fraction).round() as u8;
+    (lerp(a.0, b.0), lerp(a.1, b.1), lerp(a.2, b.2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_maps_boundaries() {
+        assert_eq!(ColorMap::Grayscale.map(0.0), (0, 0, 0));
+        assert_eq!(ColorMap::Grayscale.map(1.0), (255, 255, 255));
+        assert_eq!(ColorMap::Viridis.map(0.0), (0x44, 0x01, 0x54));
+        assert_eq!(ColorMap::Viridis.map(1.0), (0xfd, 0xe7, 0x25));
+        assert_eq!(ColorMap::Magma.map(0.0), (0x00, 0x00, 0x04));
+        assert_eq!(ColorMap::Magma.map(1.0), (0xfc, 0xfd, 0xbf));
+        assert_eq!(ColorMap::Jet.map(0.0), (0, 0, 128));
+        assert_eq!(ColorMap::Jet.map(0.5), (128, 255, 128));
+        assert_eq!(ColorMap::Jet.map(1.0), (128, 0, 0));
+    }
+
+    #[test]
+    fn test_color_maps_clamp() {
+        for color_map in [
+            ColorMap::Grayscale,
+            ColorMap::Viridis,
+            ColorMap::Magma,
+            ColorMap::Jet,
+        ] {
+            assert_eq!(color_map.map(-1.0), color_map.map(0.0));
+            assert_eq!(color_map.map(2.0), color_map.map(1.0));
+            assert_eq!(color_map.map(f32::NAN), color_map.map(0.0));
+        }
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let table = [(0, 0, 0), (100, 200, 50)];
+        assert_eq!(interpolate(&table, 0.5), (50, 100, 25));
+    }
+}
@@ -0,0 +1,192 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Basic signal processing helpers that are useful to prepare audio data
+//! before it gets visualized.
+
+/// Removes leading and trailing silence from mono audio data.
+///
+/// Returns the subslice between the first and the last sample whose absolute value
+/// exceeds `threshold`. If all samples are silent, an empty slice is returned.
+///
+/// Feeding the trimmed data to a waveform exporter makes the actual content fill the
+/// whole image.
+pub fn trim_silence(samples: &[i16], threshold: i16) -> &[i16] {
+    trim_by(samples, |x| (*x as i32).abs() <= threshold as i32)
+}
+
+/// Like [`trim_silence`] but for samples in interval `[-1.0; 1.0]`.
+pub fn trim_silence_f32(samples: &[f32], threshold: f32) -> &[f32] {
+    trim_by(samples, |x| x.abs() <= threshold)
+}
+
+/// Scales the samples in place, so that the peak reaches full scale (`1.0`), e.g. before
+/// the samples are visualized, played or saved. Silence is kept as it is.
+pub fn normalize_in_place(samples: &mut [f32]) {
+    normalize_in_place_to(samples, 1.0);
+}
+
+/// Like [`normalize_in_place`] but the peak reaches `target` instead of full scale, e.g.
+/// `0.5` for -6 dBFS.
+pub fn normalize_in_place_to(samples: &mut [f32], target: f32) {
+    assert!(target >= 0.0, "target must not be negative!");
+    let peak = samples.iter().fold(0.0_f32, |peak, x| peak.max(x.abs()));
+    if peak == 0.0 {
+        return;
+    }
+    let factor = target / peak;
+    samples.iter_mut().for_each(|x| *x *= factor);
+}
+
+/// Like [`normalize_in_place`] but for 16 bit samples. The peak reaches [`i16::MAX`].
+pub fn normalize_in_place_i16(samples: &mut [i16]) {
+    normalize_in_place_i16_to(samples, i16::MAX);
+}
+
+/// Like [`normalize_in_place_i16`] but the peak reaches `target` instead of full scale.
+pub fn normalize_in_place_i16_to(samples: &mut [i16], target: i16) {
+    assert!(target >= 0, "target must not be negative!");
+    let peak = samples.iter().map(|x| (*x as i32).abs()).max().unwrap_or(0);
+    if peak == 0 {
+        return;
+    }
+    let factor = target as f32 / peak as f32;
+    // `as` saturates at the bounds of i16
+    samples
+        .iter_mut()
+        .for_each(|x| *x = (*x as f32 * factor).round() as i16);
+}
+
+/// Returns the latest `n` samples, i.e. the last `n` elements of the buffer, e.g. of the
+/// audio history in a transform function of the live window. Returns the whole buffer,
+/// if it is shorter than `n`.
+pub fn latest_n(buffer: &[f32], n: usize) -> &[f32] {
+    &buffer[buffer.len().saturating_sub(n)..]
+}
+
+/// Replaces non-finite samples, e.g. from a buggy filter, by valid samples.
+///
+/// `NaN` becomes `0.0` and `+-Inf` becomes `+-1.0`. Finite samples are kept. Logs a
+/// warning with the number of replaced samples, if there are any.
+pub fn sanitize_f32(samples: &[f32]) -> Vec<f32> {
+    let non_finite = samples.iter().filter(|x| !x.is_finite()).count();
+    if non_finite > 0 {
+        log::warn!(
+            "{} of {} samples are NaN or infinite; they are replaced by 0.0 or +-1.0",
+            non_finite,
+            samples.len()
+        );
+    }
+    samples
+        .iter()
+        .map(|x| {
+            if x.is_nan() {
+                0.0
+            } else if x.is_infinite() {
+                x.signum()
+            } else {
+                *x
+            }
+        })
+        .collect()
+}
+
+/// Returns the subslice between the first and the last sample that is not silent.
+fn trim_by<T>(samples: &[T], is_silent: impl Fn(&T) -> bool) -> &[T] {
+    let start = samples.iter().position(|x| !is_silent(x));
+    let end = samples.iter().rposition(|x| !is_silent(x));
+    match (start, end) {
+        (Some(start), Some(end)) => &samples[start..=end],
+        _ => &samples[0..0],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trim_silence() {
+        let samples = [0, 3, -2, 100, -5, -200, 7, 0, 1];
+        assert_eq!(trim_silence(&samples, 10), &[100, -5, -200]);
+        assert_eq!(trim_silence(&samples, 0), &[3, -2, 100, -5, -200, 7, 0, 1]);
+        assert_eq!(trim_silence(&[i16::MIN, 0], 10), &[i16::MIN]);
+    }
+
+    #[test]
+    fn test_trim_silence_all_silent() {
+        assert!(trim_silence(&[0, 1, -1, 0], 5).is_empty());
+        assert!(trim_silence(&[], 5).is_empty());
+        assert!(trim_silence_f32(&[0.0, 0.01, -0.01], 0.1).is_empty());
+    }
+
+    #[test]
+    fn test_trim_silence_f32() {
+        let samples = [0.0, 0.01, 0.5, -0.02, -0.7, 0.0];
+        assert_eq!(trim_silence_f32(&samples, 0.1), &[0.5, -0.02, -0.7]);
+    }
+
+    #[test]
+    fn test_normalize_in_place() {
+        let mut samples = [0.0, 0.25, -0.5];
+        normalize_in_place(&mut samples);
+        assert_eq!(samples, [0.0, 0.5, -1.0]);
+        normalize_in_place_to(&mut samples, 0.5);
+        assert_eq!(samples, [0.0, 0.25, -0.5]);
+
+        let mut silence = [0.0; 4];
+        normalize_in_place(&mut silence);
+        assert_eq!(silence, [0.0; 4]);
+    }
+
+    #[test]
+    fn test_normalize_in_place_i16() {
+        let mut samples = [0, 1000, -3000];
+        normalize_in_place_i16(&mut samples);
+        assert_eq!(samples, [0, 10922, -32767]);
+        normalize_in_place_i16_to(&mut samples, 100);
+        assert_eq!(samples, [0, 33, -100]);
+        let mut samples = [0, 1000, -2000];
+        normalize_in_place_i16_to(&mut samples, 100);
+        assert_eq!(samples, [0, 50, -100]);
+
+        let mut samples = [i16::MIN, i16::MAX];
+        normalize_in_place_i16(&mut samples);
+        assert_eq!(samples, [-32767, 32766]);
+    }
+
+    #[test]
+    fn test_latest_n() {
+        let buffer = [1.0, 2.0, 3.0, 4.0];
+        assert_eq!(latest_n(&buffer, 2), &[3.0, 4.0]);
+        assert_eq!(latest_n(&buffer, 4), &buffer);
+        assert_eq!(latest_n(&buffer, 10), &buffer);
+        assert!(latest_n(&buffer, 0).is_empty());
+    }
+
+    #[test]
+    fn test_sanitize_f32() {
+        let samples = [0.5, f32::NAN, f32::INFINITY, f32::NEG_INFINITY, -1.5];
+        assert_eq!(sanitize_f32(&samples), vec![0.5, 0.0, 1.0, -1.0, -1.5]);
+    }
+}
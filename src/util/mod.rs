@@ -24,4 +24,207 @@ SOFTWARE.
 
 //! Common utility functions required in multiple other modules.
 
+pub mod colormap;
+pub mod dsp;
+pub mod music;
 pub mod png;
+
+use plotters::chart::MeshStyle;
+use plotters::coord::ranged1d::Ranged;
+use plotters::prelude::DrawingBackend;
+use plotters::style::{Color, RGBColor};
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+/// Font family of the chart labels, if no other font is configured, e.g. with
+/// [`crate::spectrum::plotters_png_file::SpectrumChartConfig::font`].
+///
+/// "plotters" looks up fonts by their family name among the installed system fonts; this crate
+/// doesn't bundle a font. If no matching font is installed, e.g. in minimal container images,
+/// the labels stay blank. The exporters without a font option, e.g.
+/// [`crate::spectrum::cqt::spectrum_cqt_png_visualize`], always use this font.
+pub const DEFAULT_FONT: &str = "sans-serif";
+
+/// Grid (mesh) of the charts of the "plotters" exporters, e.g.
+/// [`crate::spectrum::plotters_png_file::SpectrumChartConfig::mesh`].
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum MeshMode {
+    /// Grid lines in the default colors of "plotters".
+    #[default]
+    Full,
+    /// Grid lines in the given color; the light lines are a transparent variant of it.
+    Colored(RGBColor),
+    /// Only the axes with their labels, no grid lines. Good for dense plots.
+    AxisOnly,
+    /// Neither grid lines nor axes, e.g. for minimalist figures.
+    None,
+}
+
+impl MeshMode {
+    /// Configures the mesh of a chart accordingly. The mesh still has to be drawn.
+    pub(crate) fn apply<X: Ranged, Y: Ranged, DB: DrawingBackend>(
+        self,
+        mesh: &mut MeshStyle<X, Y, DB>,
+    ) {
+        match self {
+            Self::Full => {}
+            Self::Colored(color) => {
+                mesh.bold_line_style(color.mix(0.6))
+                    .light_line_style(color.mix(0.2));
+            }
+            Self::AxisOnly => {
+                mesh.disable_mesh();
+            }
+            Self::None => {
+                mesh.disable_mesh().disable_axes();
+            }
+        }
+    }
+}
+
+/// Returns the part of the spectrum within the frequency range.
+///
+/// For example, `0..8000` keeps only the frequencies below 8 kHz of a spectrum that goes
+/// up to the Nyquist frequency. The spectrum exporters scale the x-axis to the highest
+/// frequency of the cropped spectrum.
+pub fn crop_spectrum(spectrum: &BTreeMap<u32, f32>, range: Range<u32>) -> BTreeMap<u32, f32> {
+    spectrum.range(range).map(|(fr, val)| (*fr, *val)).collect()
+}
+
+/// Averages the magnitudes of multiple spectra, e.g. of all frames of an audio file, into
+/// one representative spectrum.
+///
+/// Each frequency is averaged over the frames that contain it, i.e. frequencies missing in
+/// some frames don't lower the average. Spectra of the same FFT size and sampling rate
+/// share all frequencies. Returns an empty spectrum, if there are no frames.
+pub fn average_spectra(frames: &[BTreeMap<u32, f32>]) -> BTreeMap<u32, f32> {
+    let mut sums = BTreeMap::<u32, (f32, usize)>::new();
+    frames.iter().flatten().for_each(|(fr, val)| {
+        let (sum, count) = sums.entry(*fr).or_insert((0.0, 0));
+        *sum += *val;
+        *count += 1;
+    });
+    sums.into_iter()
+        .map(|(fr, (sum, count))| (fr, sum / count as f32))
+        .collect()
+}
+
+/// Number of zero crossings of the sinc function on each side of the kernel of [`resample`].
+const RESAMPLE_ZERO_CROSSINGS: usize = 16;
+
+/// Resamples mono audio data from `from_rate` to `to_rate` with a windowed-sinc
+/// (Blackman window) interpolation.
+///
+/// When downsampling, the cutoff of the lowpass filter is lowered to the new Nyquist
+/// frequency, so that higher frequencies are removed instead of aliased. The stopband
+/// attenuation is about 70 dB; the transition band is roughly 10% of the Nyquist frequency.
+///
+/// Quality costs time and latency: each output sample is calculated from 32 input samples
+/// (more when downsampling) and depends on 16 future input samples, i.e. ~0.4 ms at
+/// 44.1 kHz. This is fine for static data or the history of the live window but too slow
+/// for very long audio files in debug builds. The data is zero-padded at both ends.
+pub fn resample(input: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    assert!(
+        from_rate > 0 && to_rate > 0,
+        "sampling rates must be positive!"
+    );
+    if from_rate == to_rate {
+        return input.to_vec();
+    }
+    let ratio = from_rate as f64 / to_rate as f64;
+    // cutoff relative to the Nyquist frequency of the input
+    let cutoff = (1.0 / ratio).min(1.0);
+    let half_width = RESAMPLE_ZERO_CROSSINGS as f64 / cutoff;
+    let output_len = (input.len() as f64 / ratio).ceil() as usize;
+
+    (0..output_len)
+        .map(|n| {
+            // position of the output sample in the input
+            let t = n as f64 * ratio;
+            let first = (t - half_width).ceil().max(0.0) as usize;
+            let last = ((t + half_width).floor() as usize).min(input.len().saturating_sub(1));
+            (first..=last)
+                .map(|k| {
+                    let x = t - k as f64;
+                    input[k] as f64 * cutoff * sinc(cutoff * x) * blackman(x / half_width)
+                })
+                .sum::<f64>() as f32
+        })
+        .collect()
+}
+
+/// Normalized sinc function `sin(pi x) / (pi x)`.
+fn sinc(x: f64) -> f64 {
+    if x == 0.0 {
+        1.0
+    } else {
+        let x = std::f64::consts::PI * x;
+        x.sin() / x
+    }
+}
+
+/// Blackman window for `x` in `[-1.0; 1.0]`; zero outside.
+fn blackman(x: f64) -> f64 {
+    if x.abs() >= 1.0 {
+        return 0.0;
+    }
+    let phase = std::f64::consts::PI * (x + 1.0);
+    0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crop_spectrum() {
+        let spectrum = (0..=22050)
+            .step_by(50)
+            .map(|fr| (fr, fr as f32))
+            .collect::<BTreeMap<u32, f32>>();
+        let cropped = crop_spectrum(&spectrum, 0..8000);
+        assert_eq!(cropped.keys().next(), Some(&0));
+        assert_eq!(cropped.keys().last(), Some(&7950));
+        assert_eq!(cropped.len(), 160);
+        assert!(crop_spectrum(&spectrum, 30000..40000).is_empty());
+    }
+
+    #[test]
+    fn test_average_spectra() {
+        let frames = [
+            BTreeMap::from([(100, 1.0), (200, 2.0)]),
+            BTreeMap::from([(100, 3.0), (200, 4.0), (300, 5.0)]),
+        ];
+        let average = average_spectra(&frames);
+        assert_eq!(
+            average,
+            BTreeMap::from([(100, 2.0), (200, 3.0), (300, 5.0)])
+        );
+        assert!(average_spectra(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_resample() {
+        let sine = |frequency: f64, rate: u32, len: usize| {
+            (0..len)
+                .map(|i| (2.0 * std::f64::consts::PI * frequency * i as f64 / rate as f64).sin())
+                .map(|x| x as f32)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(resample(&[0.5, -0.5], 44100, 44100), vec![0.5, -0.5]);
+
+        // a 1 kHz sine stays a 1 kHz sine (apart from the zero-padded ends)
+        let resampled = resample(&sine(1000.0, 48000, 48000), 48000, 44100);
+        assert_eq!(resampled.len(), 44100);
+        let expected = sine(1000.0, 44100, 44100);
+        resampled[100..44000]
+            .iter()
+            .zip(&expected[100..44000])
+            .for_each(|(actual, expected)| assert!((actual - expected).abs() < 0.01));
+
+        // 10 kHz is above the Nyquist frequency of 8 kHz; it must be removed, not aliased
+        let resampled = resample(&sine(10000.0, 44100, 44100), 44100, 8000);
+        assert_eq!(resampled.len(), 8000);
+        assert!(resampled[100..7900].iter().all(|x| x.abs() < 0.01));
+    }
+}
@@ -0,0 +1,89 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Helpers for musical notes in equal temperament (A4 = 440 Hz).
+
+use std::fmt::{Display, Formatter};
+
+/// Names of the twelve semitones of an octave, starting at C.
+const NOTE_NAMES: [&str; 12] = [
+    "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+];
+
+/// Musical note in equal temperament. See [`nearest_note`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Note {
+    /// Name of the note without octave, e.g. `"A"` or `"C#"`.
+    pub name: &'static str,
+    /// Octave in scientific pitch notation, e.g. `4` for A4 (440 Hz).
+    pub octave: i32,
+    /// Deviation of the frequency from the exact frequency of the note in cents,
+    /// i.e. a value in interval `[-50.0; 50.0]`.
+    pub cents: f32,
+}
+
+impl Display for Note {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.name, self.octave)
+    }
+}
+
+/// Returns the nearest note in equal temperament (A4 = 440 Hz) for a frequency
+/// in Hertz. The frequency must be positive.
+pub fn nearest_note(frequency: f32) -> Note {
+    assert!(frequency > 0.0, "frequency must be positive!");
+    // MIDI note number; 69 is A4
+    let midi = 69.0 + 12.0 * (frequency / 440.0).log2();
+    let nearest_midi = midi.round();
+    let midi_i = nearest_midi as i32;
+    Note {
+        name: NOTE_NAMES[midi_i.rem_euclid(12) as usize],
+        octave: midi_i.div_euclid(12) - 1,
+        cents: (midi - nearest_midi) * 100.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nearest_note() {
+        let a4 = nearest_note(440.0);
+        assert_eq!(a4.to_string(), "A4");
+        assert!(a4.cents.abs() < 0.01);
+
+        assert_eq!(nearest_note(261.63).to_string(), "C4");
+        assert_eq!(nearest_note(32.70).to_string(), "C1");
+        assert_eq!(nearest_note(466.16).to_string(), "A#4");
+
+        // a bit too high
+        let sharp_a4 = nearest_note(443.0);
+        assert_eq!(sharp_a4.to_string(), "A4");
+        assert!((sharp_a4.cents - 11.76).abs() < 0.1);
+        // a bit too low; nearest note is still A4
+        let flat_a4 = nearest_note(430.0);
+        assert_eq!(flat_a4.to_string(), "A4");
+        assert!(flat_a4.cents < -39.0);
+    }
+}
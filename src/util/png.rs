@@ -22,25 +22,186 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 use std::fs::File;
-use std::io::BufWriter;
+use std::io::{BufWriter, Write};
 use std::path::Path;
 
-/// Writes RGB-bytes into the given file using [`png`]-crate.
-pub fn write_png_file_u8(file: &Path, rgb_data: &[u8], image_width: u32, image_height: u32) {
-    let file = File::create(file).unwrap();
-    let mut writer = BufWriter::new(file);
+/// Options of the PNG encoding, i.e. the resolution and the compression, of the exporters that
+/// write their images with this module.
+///
+/// See e.g. [`crate::waveform::png_file::WaveformPngConfig::png`]. The exporters that draw with
+/// the "plotters" crate write their files themselves and don't support these options.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct PngOptions {
+    /// Resolution stored in the PNG file (pHYs chunk), e.g. 300 for print, so that layout
+    /// tools size the image correctly. If `None`, no resolution is stored and most tools
+    /// assume 72 dpi. Default is `None`.
+    pub dpi: Option<u32>,
+    /// Compression of the PNG files, e.g. [`png::Compression::Fast`] for batch jobs or
+    /// [`png::Compression::Best`] for archival. Default is [`png::Compression::Default`].
+    pub compression: png::Compression,
+}
+
+/// Encodes RGB-bytes as PNG into any [`Write`] using [`png`]-crate, e.g. into an archive,
+/// a HTTP response body or a `Vec<u8>`.
+pub fn write_png<W: Write>(writer: W, rgb_data: &[u8], image_width: u32, image_height: u32) {
+    try_write_png(
+        writer,
+        rgb_data,
+        image_width,
+        image_height,
+        &PngOptions::default(),
+    )
+    .unwrap()
+}
+
+/// Like [`write_png`] but returns an error instead of panicking, if the PNG can't be
+/// encoded or written.
+///
+/// The `options` set the resolution that is stored in the PNG (pHYs chunk), so that layout
+/// tools size the image correctly, and the compression, that trades the encoding time for
+/// the file size. See [`PngOptions`].
+pub fn try_write_png<W: Write>(
+    writer: W,
+    rgb_data: &[u8],
+    image_width: u32,
+    image_height: u32,
+    options: &PngOptions,
+) -> Result<(), png::EncodingError> {
+    try_write_png_8bit(
+        writer,
+        rgb_data,
+        image_width,
+        image_height,
+        png::ColorType::Rgb,
+        options,
+    )
+}
+
+/// Like [`try_write_png`] but for RGBA-bytes, i.e. with an alpha channel, e.g. for images
+/// that are composited over other media.
+pub fn try_write_png_rgba<W: Write>(
+    writer: W,
+    rgba_data: &[u8],
+    image_width: u32,
+    image_height: u32,
+    options: &PngOptions,
+) -> Result<(), png::EncodingError> {
+    try_write_png_8bit(
+        writer,
+        rgba_data,
+        image_width,
+        image_height,
+        png::ColorType::Rgba,
+        options,
+    )
+}
 
-    let mut encoder = png::Encoder::new(&mut writer, image_width, image_height);
-    encoder.set_color(png::ColorType::Rgb);
+/// Encodes 8 bit image data of the given color type. See [`try_write_png`].
+fn try_write_png_8bit<W: Write>(
+    writer: W,
+    data: &[u8],
+    image_width: u32,
+    image_height: u32,
+    color_type: png::ColorType,
+    options: &PngOptions,
+) -> Result<(), png::EncodingError> {
+    let mut encoder = png::Encoder::new(writer, image_width, image_height);
+    encoder.set_color(color_type);
     encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_compression(options.compression);
+    encoder.set_pixel_dims(options.dpi.map(pixel_dims_from_dpi));
+    let mut writer = encoder.write_header()?;
+
+    writer.write_image_data(data)
+}
+
+/// Writes RGB-bytes into the given file. Wrapper around [`write_png`]. See
+/// [`try_write_png_file_u8`] to store a resolution.
+pub fn write_png_file_u8(file: &Path, rgb_data: &[u8], image_width: u32, image_height: u32) {
+    try_write_png_file_u8(
+        file,
+        rgb_data,
+        image_width,
+        image_height,
+        &PngOptions::default(),
+    )
+    .unwrap()
+}
+
+/// Like [`write_png_file_u8`] but returns an error instead of panicking, if the file
+/// can't be created or the PNG can't be encoded.
+pub fn try_write_png_file_u8(
+    file: &Path,
+    rgb_data: &[u8],
+    image_width: u32,
+    image_height: u32,
+    options: &PngOptions,
+) -> Result<(), png::EncodingError> {
+    let file = File::create(file)?;
+    try_write_png(
+        BufWriter::new(file),
+        rgb_data,
+        image_width,
+        image_height,
+        options,
+    )
+}
+
+/// Encodes 16 bit grayscale values as PNG into any [`Write`]. Unlike 8 bit images, subtle
+/// gradients are not quantized to 256 levels, e.g. for high-dynamic-range spectrograms.
+pub fn write_png_u16_grayscale<W: Write>(
+    writer: W,
+    data: &[u16],
+    image_width: u32,
+    image_height: u32,
+) {
+    let mut encoder = png::Encoder::new(writer, image_width, image_height);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Sixteen);
     let mut writer = encoder.write_header().unwrap();
 
-    writer.write_image_data(rgb_data).unwrap();
+    // PNG requires big-endian byte order
+    let bytes = data
+        .iter()
+        .flat_map(|x| x.to_be_bytes())
+        .collect::<Vec<u8>>();
+    writer.write_image_data(&bytes).unwrap();
+}
+
+/// Writes 16 bit grayscale values into the given file. Wrapper around
+/// [`write_png_u16_grayscale`].
+pub fn write_png_file_u16_grayscale(
+    file: &Path,
+    data: &[u16],
+    image_width: u32,
+    image_height: u32,
+) {
+    let file = File::create(file).unwrap();
+    write_png_u16_grayscale(BufWriter::new(file), data, image_width, image_height)
 }
 
 /// Wrapper around [`write_png_file_u8`] that takes a vector of vectors with RGB-tuples.
 /// (rows, cols).
 pub fn write_png_file_rgb_tuples(file: &Path, rgb_image: &[Vec<(u8, u8, u8)>]) {
+    try_write_png_file_rgb_tuples(file, rgb_image, &PngOptions::default()).unwrap()
+}
+
+/// Like [`write_png_file_rgb_tuples`] but with the given [`PngOptions`].
+pub fn write_png_file_rgb_tuples_with_options(
+    file: &Path,
+    rgb_image: &[Vec<(u8, u8, u8)>],
+    options: &PngOptions,
+) {
+    try_write_png_file_rgb_tuples(file, rgb_image, options).unwrap()
+}
+
+/// Like [`write_png_file_rgb_tuples`] but returns an error instead of panicking.
+/// See [`try_write_png_file_u8`].
+pub fn try_write_png_file_rgb_tuples(
+    file: &Path,
+    rgb_image: &[Vec<(u8, u8, u8)>],
+    options: &PngOptions,
+) -> Result<(), png::EncodingError> {
     let width = rgb_image[0].len() as u32;
     let height = rgb_image.len() as u32;
 
@@ -53,5 +214,148 @@ pub fn write_png_file_rgb_tuples(file: &Path, rgb_image: &[Vec<(u8, u8, u8)>]) {
         .copied()
         .collect::<Vec<u8>>();
 
-    write_png_file_u8(file, &rgb_data, width, height)
+    try_write_png_file_u8(file, &rgb_data, width, height, options)
+}
+
+/// Like [`try_write_png_file_rgb_tuples`] but writes an RGBA image in which all pixels of the
+/// `transparent` color (e.g. the white background) are fully transparent and all other
+/// pixels are opaque.
+pub fn try_write_png_file_rgb_tuples_transparent(
+    file: &Path,
+    rgb_image: &[Vec<(u8, u8, u8)>],
+    transparent: (u8, u8, u8),
+    options: &PngOptions,
+) -> Result<(), png::EncodingError> {
+    let width = rgb_image[0].len() as u32;
+    let height = rgb_image.len() as u32;
+
+    let rgba_data = rgb_image
+        .iter()
+        .flat_map(|row| row.iter())
+        .flat_map(|pixel| {
+            let (r, g, b) = *pixel;
+            let alpha = if *pixel == transparent { 0 } else { 255 };
+            [r, g, b, alpha]
+        })
+        .collect::<Vec<u8>>();
+
+    let file = File::create(file)?;
+    try_write_png_rgba(BufWriter::new(file), &rgba_data, width, height, options)
+}
+
+/// Converts dots per inch into the pixels per meter of the pHYs chunk.
+fn pixel_dims_from_dpi(dpi: u32) -> png::PixelDimensions {
+    let pixels_per_meter = (dpi as f64 / 0.0254).round() as u32;
+    png::PixelDimensions {
+        xppu: pixels_per_meter,
+        yppu: pixels_per_meter,
+        unit: png::Unit::Meter,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::testutil::TEST_OUT_DIR;
+
+    #[test]
+    fn test_write_png_into_vec() {
+        let mut png_bytes = Vec::new();
+        // 2x1 image: red and green pixel
+        write_png(&mut png_bytes, &[255, 0, 0, 0, 255, 0], 2, 1);
+        assert!(png_bytes.starts_with(&[0x89, b'P', b'N', b'G']));
+    }
+
+    #[test]
+    fn test_write_png_u16_grayscale_round_trip() {
+        let data = [0, 1, 0x1234, u16::MAX];
+        let mut png_bytes = Vec::new();
+        write_png_u16_grayscale(&mut png_bytes, &data, 2, 2);
+
+        let mut reader = png::Decoder::new(png_bytes.as_slice()).read_info().unwrap();
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).unwrap();
+        assert_eq!(info.bit_depth, png::BitDepth::Sixteen);
+        assert_eq!(info.color_type, png::ColorType::Grayscale);
+        let decoded = buf[..info.buffer_size()]
+            .chunks_exact(2)
+            .map(|x| u16::from_be_bytes([x[0], x[1]]))
+            .collect::<Vec<_>>();
+        assert_eq!(decoded, data);
+    }
+
+    #[test]
+    fn test_try_write_png_file_missing_directory() {
+        let result = try_write_png_file_rgb_tuples(
+            Path::new("test/out/does/not/exist/image.png"),
+            &[vec![(0, 0, 0)]],
+            &PngOptions::default(),
+        );
+        assert!(matches!(result, Err(png::EncodingError::IoError(_))));
+    }
+
+    #[test]
+    fn test_try_write_png_dpi() {
+        let mut png_bytes = Vec::new();
+        try_write_png(
+            &mut png_bytes,
+            &[255, 0, 0],
+            1,
+            1,
+            &PngOptions {
+                dpi: Some(300),
+                ..PngOptions::default()
+            },
+        )
+        .unwrap();
+        let reader = png::Decoder::new(png_bytes.as_slice()).read_info().unwrap();
+        let pixel_dims = reader.info().pixel_dims.unwrap();
+        // 300 dpi = 11811 pixels per meter
+        assert_eq!((pixel_dims.xppu, pixel_dims.yppu), (11811, 11811));
+        assert!(matches!(pixel_dims.unit, png::Unit::Meter));
+    }
+
+    #[test]
+    fn test_try_write_png_file_rgb_tuples_transparent() {
+        let path = Path::new(TEST_OUT_DIR).join("png_rgb_tuples_transparent.png");
+        try_write_png_file_rgb_tuples_transparent(
+            &path,
+            &[vec![(255, 255, 255), (255, 0, 0)]],
+            (255, 255, 255),
+            &PngOptions::default(),
+        )
+        .unwrap();
+
+        let file = File::open(&path).unwrap();
+        let mut reader = png::Decoder::new(file).read_info().unwrap();
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).unwrap();
+        assert_eq!(info.color_type, png::ColorType::Rgba);
+        assert_eq!(&buf[..8], &[255, 255, 255, 0, 255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_try_write_png_compression() {
+        // a smooth gradient compresses well
+        let rgb_data = (0..256 * 256 * 3)
+            .map(|i| (i / 3 % 256) as u8)
+            .collect::<Vec<_>>();
+        let encode = |compression| {
+            let mut png_bytes = Vec::new();
+            let options = PngOptions {
+                compression,
+                ..PngOptions::default()
+            };
+            try_write_png(&mut png_bytes, &rgb_data, 256, 256, &options).unwrap();
+            png_bytes
+        };
+        let fast = encode(png::Compression::Fast);
+        let best = encode(png::Compression::Best);
+        assert!(best.len() <= fast.len());
+
+        let mut reader = png::Decoder::new(best.as_slice()).read_info().unwrap();
+        let mut buf = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut buf).unwrap();
+        assert_eq!(buf, rgb_data);
+    }
 }
@@ -0,0 +1,153 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+//! Static goniometer (Lissajous figure) of stereo audio data which exports to a PNG file.
+//! It helps to check the phase relation of the left and the right channel.
+
+use crate::util::DEFAULT_FONT;
+use crate::ChannelInterleavement;
+use plotters::prelude::*;
+use std::path::PathBuf;
+
+/// Calculates the correlation coefficient of the left and the right channel.
+///
+/// `1.0` means both channels are equal (mono), `-1.0` means they are out of phase, and
+/// values around `0.0` mean they are unrelated, e.g. a wide stereo image.
+///
+/// Returns `None`, if a channel is silent or there is no audio data.
+pub fn stereo_correlation(left: &[i16], right: &[i16]) -> Option<f32> {
+    let (mut lr, mut ll, mut rr) = (0.0_f64, 0.0_f64, 0.0_f64);
+    for (l, r) in left.iter().zip(right) {
+        let (l, r) = (*l as f64, *r as f64);
+        lr += l * r;
+        ll += l * l;
+        rr += r * r;
+    }
+    if ll == 0.0 || rr == 0.0 {
+        return None;
+    }
+    Some((lr / (ll * rr).sqrt()) as f32)
+}
+
+/// Visualizes stereo audio data as goniometer (Lissajous figure) in a PNG file using the
+/// "plotters" crate.
+///
+/// Each pair of samples becomes one point with the left sample on the x-axis and the right
+/// sample on the y-axis. Mono audio data results in a diagonal line from bottom left to
+/// top right, audio data with channels out of phase in a diagonal line from top left to
+/// bottom right. The caption shows the [`stereo_correlation`].
+///
+/// # Parameters
+/// - `interleaved` Stereo audio samples.
+/// - `interleavement` Interleavement of the stereo audio samples.
+/// - `directory` Directory of the PNG file.
+/// - `filename` Name of the PNG file.
+pub fn goniometer_png_visualize(
+    interleaved: &[i16],
+    interleavement: ChannelInterleavement,
+    directory: &str,
+    filename: &str,
+) {
+    assert_eq!(
+        0,
+        interleaved.len() % 2,
+        "If stereo is provided, the length of the audio data must be even!"
+    );
+    let (left, right) = interleavement.to_channel_data(interleaved);
+
+    let mut path = PathBuf::new();
+    path.push(directory);
+    path.push(filename);
+
+    let caption = stereo_correlation(&left, &right).map_or_else(
+        || String::from("goniometer"),
+        |correlation| format!("goniometer (correlation: {:.2})", correlation),
+    );
+    let max = i16::MAX as f32;
+
+    let root = BitMapBackend::new(&path, (800, 800)).into_drawing_area();
+    root.fill(&WHITE).unwrap();
+    let mut chart = ChartBuilder::on(&root)
+        .caption(caption, (DEFAULT_FONT, 20).into_font())
+        .margin(5)
+        .x_label_area_size(60)
+        .y_label_area_size(60)
+        .build_cartesian_2d(-max..max, -max..max)
+        .unwrap();
+
+    chart
+        .configure_mesh()
+        .x_desc("left")
+        .y_desc("right")
+        .draw()
+        .unwrap();
+
+    chart
+        .draw_series(
+            left.iter()
+                .zip(right.iter())
+                .map(|(l, r)| Pixel::new((*l as f32, *r as f32), RED)),
+        )
+        .unwrap();
+
+    root.present().unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::testutil::sine::sine_wave_audio_data;
+    use crate::tests::testutil::TEST_OUT_DIR;
+
+    #[test]
+    fn test_stereo_correlation() {
+        let left = sine_wave_audio_data(50.0, 44100, 100);
+        let inverted = left.iter().map(|x| -x).collect::<Vec<_>>();
+        assert!((stereo_correlation(&left, &left).unwrap() - 1.0).abs() < 0.001);
+        assert!((stereo_correlation(&left, &inverted).unwrap() + 1.0).abs() < 0.001);
+        assert_eq!(stereo_correlation(&left, &vec![0; left.len()]), None);
+    }
+
+    #[test]
+    fn test_goniometer_png_visualize() {
+        let left = sine_wave_audio_data(50.0, 44100, 1000);
+        // same frequency but phase shifted by a quarter period: a circle
+        let right = sine_wave_audio_data(50.0, 44100, 1005)[220..]
+            .iter()
+            .copied()
+            .take(left.len())
+            .collect::<Vec<_>>();
+        for (right, filename) in [
+            (left.clone(), "goniometer_png_visualize_mono.png"),
+            (right, "goniometer_png_visualize_phase_shifted.png"),
+        ] {
+            goniometer_png_visualize(
+                &ChannelInterleavement::LRLR.from_channel_data(&left, &right),
+                ChannelInterleavement::LRLR,
+                TEST_OUT_DIR,
+                filename,
+            );
+        }
+    }
+}
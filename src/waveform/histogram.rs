@@ -0,0 +1,232 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+//! Static amplitude histogram which exports to a PNG file. It shows how often each
+//! amplitude occurs, e.g. to detect compression or limiting.
+
+use crate::util::DEFAULT_FONT;
+use plotters::prelude::*;
+use std::path::PathBuf;
+
+/// Number of possible values of a 16 bit sample.
+const VALUE_COUNT: i32 = 1 << 16;
+
+/// Level of the smallest non-zero amplitude of a 16 bit sample in dBFS, approx. -90.3 dB.
+/// It is the lower edge of the first bucket with [`AmplitudeHistogramConfig::log_bins`].
+const MIN_DB: f32 = -90.309;
+
+/// Configuration for [`amplitude_histogram_with_config`] and
+/// [`amplitude_histogram_png_visualize_with_config`].
+/// Use [`AmplitudeHistogramConfig::default`] and override the fields you need.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AmplitudeHistogramConfig {
+    /// If `true`, the buckets are spaced logarithmically over the magnitude of the samples
+    /// in dBFS, from approx. -90 dB (first bucket, also holds silence) to 0 dB (last
+    /// bucket). This reveals the distribution of low-level content, e.g. reverb tails or
+    /// the noise floor, that linear buckets collapse into the buckets around zero.
+    /// Default is `false`, i.e. linear buckets over the whole range of [`i16`].
+    pub log_bins: bool,
+}
+
+/// Counts the samples into `bins` amplitude buckets of equal width. The buckets cover
+/// the whole range of [`i16`], from [`i16::MIN`] (first bucket) to [`i16::MAX`] (last bucket).
+///
+/// Returns the number of samples per bucket.
+pub fn amplitude_histogram(samples: &[i16], bins: usize) -> Vec<usize> {
+    amplitude_histogram_with_config(samples, bins, &AmplitudeHistogramConfig::default())
+}
+
+/// Like [`amplitude_histogram`] but with additional configuration, e.g. logarithmic
+/// buckets. See [`AmplitudeHistogramConfig`].
+pub fn amplitude_histogram_with_config(
+    samples: &[i16],
+    bins: usize,
+    cfg: &AmplitudeHistogramConfig,
+) -> Vec<usize> {
+    assert!(
+        bins > 0 && bins <= VALUE_COUNT as usize,
+        "bins must be in range 1..=65536!"
+    );
+    let mut counts = vec![0; bins];
+    for sample in samples {
+        let bin = if cfg.log_bins {
+            log_bin(*sample, bins)
+        } else {
+            let offset = (*sample as i32 - i16::MIN as i32) as usize;
+            offset * bins / VALUE_COUNT as usize
+        };
+        counts[bin] += 1;
+    }
+    counts
+}
+
+/// Index of the logarithmic bucket of the sample. See [`AmplitudeHistogramConfig::log_bins`].
+fn log_bin(sample: i16, bins: usize) -> usize {
+    if sample == 0 {
+        return 0;
+    }
+    let db = 20.0 * ((sample as f32).abs() / -(i16::MIN as f32)).log10();
+    let bin = ((db - MIN_DB) / -MIN_DB * bins as f32).max(0.0) as usize;
+    bin.min(bins - 1)
+}
+
+/// Visualizes the amplitude histogram (see [`amplitude_histogram`]) of mono audio data as
+/// bar chart in a PNG file using the "plotters" crate.
+///
+/// A healthy recording shows a roughly Gaussian distribution around zero. A compressed
+/// or limited recording shows spikes at the extremes.
+///
+/// # Parameters
+/// - `samples` Mono audio samples.
+/// - `bins` Number of amplitude buckets, e.g. 200.
+/// - `directory` Directory of the PNG file.
+/// - `filename` Name of the PNG file.
+pub fn amplitude_histogram_png_visualize(
+    samples: &[i16],
+    bins: usize,
+    directory: &str,
+    filename: &str,
+) {
+    amplitude_histogram_png_visualize_with_config(
+        samples,
+        bins,
+        directory,
+        filename,
+        &AmplitudeHistogramConfig::default(),
+    )
+}
+
+/// Like [`amplitude_histogram_png_visualize`] but with additional configuration, e.g.
+/// logarithmic buckets. See [`AmplitudeHistogramConfig`].
+///
+/// With logarithmic buckets, the x-axis shows the magnitude in dBFS.
+pub fn amplitude_histogram_png_visualize_with_config(
+    samples: &[i16],
+    bins: usize,
+    directory: &str,
+    filename: &str,
+    cfg: &AmplitudeHistogramConfig,
+) {
+    let counts = amplitude_histogram_with_config(samples, bins, cfg);
+
+    let mut path = PathBuf::new();
+    path.push(directory);
+    path.push(filename);
+
+    // empty audio data: keep a valid y-axis
+    let max = counts.iter().copied().max().unwrap_or(0).max(1);
+    let (x_range, x_desc) = if cfg.log_bins {
+        (MIN_DB..0.0, "amplitude (dBFS)")
+    } else {
+        (i16::MIN as f32..i16::MAX as f32 + 1.0, "amplitude")
+    };
+    let bin_width = (x_range.end - x_range.start) / bins as f32;
+
+    let root = BitMapBackend::new(&path, (1500, 700)).into_drawing_area();
+    root.fill(&WHITE).unwrap();
+    let mut chart = ChartBuilder::on(&root)
+        .caption("amplitude histogram", (DEFAULT_FONT, 20).into_font())
+        .margin(5)
+        .x_label_area_size(60)
+        .y_label_area_size(60)
+        .build_cartesian_2d(x_range.clone(), 0.0..max as f32)
+        .unwrap();
+
+    chart
+        .configure_mesh()
+        .x_desc(x_desc)
+        .y_desc("number of samples")
+        .draw()
+        .unwrap();
+
+    chart
+        .draw_series(counts.iter().enumerate().map(|(bin, count)| {
+            let begin = x_range.start + bin as f32 * bin_width;
+            Rectangle::new(
+                [(begin, 0.0), (begin + bin_width, *count as f32)],
+                RED.filled(),
+            )
+        }))
+        .unwrap();
+
+    root.present().unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::testutil::sine::sine_wave_audio_data;
+    use crate::tests::testutil::TEST_OUT_DIR;
+
+    #[test]
+    fn test_amplitude_histogram() {
+        let counts = amplitude_histogram(&[i16::MIN, -1, 0, 1, i16::MAX, i16::MAX], 4);
+        assert_eq!(counts, vec![1, 1, 2, 2]);
+        assert_eq!(amplitude_histogram(&[], 10), vec![0; 10]);
+    }
+
+    #[test]
+    fn test_amplitude_histogram_log_bins() {
+        let cfg = AmplitudeHistogramConfig { log_bins: true };
+        // 0 dB, 0 dB, approx. -6 dB, approx. -84 dB, silence
+        let samples = [i16::MIN, i16::MAX, 16384, -2, 0];
+        let counts = amplitude_histogram_with_config(&samples, 3, &cfg);
+        assert_eq!(counts, vec![2, 0, 3]);
+        let counts = amplitude_histogram_with_config(&samples, 15, &cfg);
+        assert_eq!(counts[14], 3);
+        assert_eq!(counts.iter().sum::<usize>(), samples.len());
+    }
+
+    #[test]
+    fn test_amplitude_histogram_png_visualize() {
+        // a sine wave clipped at half of the full scale, like a hard limiter would do
+        let samples = sine_wave_audio_data(50.0, 44100, 1000)
+            .iter()
+            .map(|x| (*x).clamp(i16::MIN / 2, i16::MAX / 2))
+            .collect::<Vec<_>>();
+        amplitude_histogram_png_visualize(
+            &samples,
+            200,
+            TEST_OUT_DIR,
+            "amplitude_histogram_png_visualize_limited_example.png",
+        );
+    }
+
+    #[test]
+    fn test_amplitude_histogram_png_visualize_log_bins() {
+        // a decaying sine, like a reverb tail
+        let samples = sine_wave_audio_data(50.0, 44100, 1000)
+            .iter()
+            .enumerate()
+            .map(|(i, x)| (*x as f32 * (-(i as f32) / 4410.0).exp()) as i16)
+            .collect::<Vec<_>>();
+        amplitude_histogram_png_visualize_with_config(
+            &samples,
+            200,
+            TEST_OUT_DIR,
+            "amplitude_histogram_png_visualize_log_bins_example.png",
+            &AmplitudeHistogramConfig { log_bins: true },
+        );
+    }
+}
@@ -25,5 +25,10 @@ SOFTWARE.
 //! This module focuses on static visualization. For dynamic visualization,
 //! look into the [`crate::dynamic`] module + corresponding examples in `examples/`.
 
+pub mod goniometer;
+pub mod histogram;
+pub mod onset;
 pub mod plotters_png_file;
 pub mod png_file;
+pub mod power;
+pub mod zcr;
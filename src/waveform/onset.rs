@@ -0,0 +1,143 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Energy-based onset detection, e.g. to mark the hits of drums and percussion in a
+//! waveform. See [`detect_onsets`].
+
+use crate::waveform::power::{map_windows_of_len, mean_power};
+
+/// Configuration of [`detect_onsets`].
+/// Use [`OnsetConfig::default`] and override the fields you need.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OnsetConfig {
+    /// Number of samples per window of the energy envelope, e.g. 512 (~12 ms at 44.1 kHz).
+    /// Short windows locate the onsets more precisely but are more sensitive to noise.
+    /// Default is 512.
+    pub window_len: usize,
+    /// Level that the energy envelope must exceed, relative to the highest energy of all
+    /// windows, in `(0.0; 1.0]`. Default is 0.1, i.e. -10 dB below the loudest window.
+    pub threshold: f32,
+    /// Minimum number of samples between two onsets, e.g. to ignore the decay of a hit.
+    /// Default is 4410 (100 ms at 44.1 kHz).
+    pub min_interval: usize,
+}
+
+impl OnsetConfig {
+    /// Checks the configuration. Returns a description of the first problem.
+    pub(crate) fn validate(&self) -> Result<(), &'static str> {
+        if self.window_len == 0 {
+            return Err("window_len must be positive!");
+        }
+        if !(self.threshold > 0.0 && self.threshold <= 1.0) {
+            return Err("threshold must be in (0.0; 1.0]!");
+        }
+        Ok(())
+    }
+}
+
+impl Default for OnsetConfig {
+    fn default() -> Self {
+        Self {
+            window_len: 512,
+            threshold: 0.1,
+            min_interval: 4410,
+        }
+    }
+}
+
+/// Detects onsets (transients) in mono audio data with an energy envelope: an onset is a
+/// window whose mean power exceeds the threshold while the previous window didn't.
+///
+/// Returns the sample index of the beginning of each onset window, in ascending order.
+/// Silent audio data has no onsets.
+pub fn detect_onsets(samples: &[f32], cfg: &OnsetConfig) -> Vec<usize> {
+    if let Err(reason) = cfg.validate() {
+        panic!("{}", reason);
+    }
+    // mean power per window, like crate::waveform::power::power_envelope
+    let energies = map_windows_of_len(samples, cfg.window_len, mean_power);
+    let max = energies.iter().copied().fold(0.0, f32::max);
+    if max == 0.0 {
+        return Vec::new();
+    }
+    let threshold = max * cfg.threshold;
+
+    let mut onsets: Vec<usize> = Vec::new();
+    let mut prev_energy = 0.0;
+    for (i, energy) in energies.iter().enumerate() {
+        let index = i * cfg.window_len;
+        let is_rising_edge = prev_energy < threshold && *energy >= threshold;
+        let is_far_enough = onsets
+            .last()
+            .filter(|last| index - **last < cfg.min_interval)
+            .is_none();
+        if is_rising_edge && is_far_enough {
+            onsets.push(index);
+        }
+        prev_energy = *energy;
+    }
+    onsets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Decaying noise bursts ("hits") at the given sample indices.
+    fn hits(len: usize, positions: &[usize]) -> Vec<f32> {
+        let mut samples = vec![0.0; len];
+        for position in positions {
+            samples
+                .iter_mut()
+                .skip(*position)
+                .take(2000)
+                .enumerate()
+                .for_each(|(i, x)| {
+                    let noise = if i % 3 == 0 { 1.0 } else { -0.5 };
+                    *x = noise * (-(i as f32) / 400.0).exp();
+                });
+        }
+        samples
+    }
+
+    #[test]
+    fn test_detect_onsets() {
+        let samples = hits(44100, &[1024, 20480, 30720]);
+        assert_eq!(
+            detect_onsets(&samples, &OnsetConfig::default()),
+            vec![1024, 20480, 30720]
+        );
+        // the last two hits are closer than the minimum interval
+        let cfg = OnsetConfig {
+            min_interval: 15000,
+            ..OnsetConfig::default()
+        };
+        assert_eq!(detect_onsets(&samples, &cfg), vec![1024, 20480]);
+    }
+
+    #[test]
+    fn test_detect_onsets_silence() {
+        assert!(detect_onsets(&[0.0; 4096], &OnsetConfig::default()).is_empty());
+        assert!(detect_onsets(&[], &OnsetConfig::default()).is_empty());
+    }
+}
@@ -23,66 +23,178 @@ SOFTWARE.
 */
 //! Static waveform visualization which exports the waveform to a PNG file.
 
+use crate::util::{MeshMode, DEFAULT_FONT};
+use crate::waveform::png_file::{channel_file_prefix, downmix, ChannelSamples, StereoHandling};
 use crate::Channels;
+use plotters::coord::Shift;
 use plotters::prelude::*;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Configuration for [`waveform_static_plotters_png_visualize_with_config`].
+/// Use [`WaveformChartConfig::default`] and override the fields you need.
+#[derive(Debug, Clone, Default)]
+pub struct WaveformChartConfig {
+    /// If `true`, the y-axis is flipped, i.e. negative sample values are drawn at the top.
+    /// By default, positive values are drawn at the top, like in
+    /// [`crate::waveform::png_file::waveform_static_png_visualize`].
+    pub invert_y: bool,
+    /// How stereo and multichannel audio data is visualized. With
+    /// [`StereoHandling::Stacked`], each channel gets its own chart in the same file.
+    /// Default is [`StereoHandling::SplitTwoFiles`].
+    pub stereo: StereoHandling,
+    /// Draws a dot at each sample on top of the line, e.g. to show the discrete samples of
+    /// a signal with a low sampling rate. Default is `false`.
+    pub draw_sample_dots: bool,
+    /// Grid of the chart. Default is [`MeshMode::Full`].
+    pub mesh: MeshMode,
+    /// Font family of the caption and the labels, e.g. `"DejaVu Sans"`, if [`DEFAULT_FONT`]
+    /// isn't installed. If `None`, [`DEFAULT_FONT`] is used. The family must be installed on
+    /// the system; no font is bundled and there is no fallback, i.e. the labels stay blank.
+    pub font: Option<String>,
+    /// Caption of each chart. If `None`, the caption is `"y=music(t)"`. An empty string
+    /// suppresses the caption.
+    pub title: Option<&'static str>,
+    /// Color of the caption. If `None`, the caption is black.
+    pub caption_color: Option<RGBColor>,
+}
 
 /// Visualizes audio as a waveform in a png file using "plotters" crate.
+///
 /// If the data is stereo, it creates two files (with "left_" and "right_" prefix).
+/// For more channels, it creates one file per channel (with "channel_0_", "channel_1_", ... prefix).
+/// See [`WaveformChartConfig::stereo`] for alternatives.
+///
+/// Returns the paths of all written files, like
+/// [`crate::waveform::png_file::waveform_static_png_visualize`].
 pub fn waveform_static_plotters_png_visualize(
     samples: &[i16],
     channels: Channels,
     directory: &str,
     filename: &str,
-) {
-    if channels.is_stereo() {
-        assert_eq!(
-            0,
-            samples.len() % 2,
-            "If stereo is provided, the length of the audio data must be even!"
-        );
-        let (left, right) = channels.stereo_interleavement().to_channel_data(samples);
-        waveform_static_plotters_png_visualize(
-            &left,
-            Channels::Mono,
-            directory,
-            &format!("left_{}", filename),
-        );
-        waveform_static_plotters_png_visualize(
-            &right,
-            Channels::Mono,
-            directory,
-            &format!("right_{}", filename),
-        );
-        return;
+) -> Vec<PathBuf> {
+    waveform_static_plotters_png_visualize_with_config(
+        samples,
+        channels,
+        directory,
+        filename,
+        &WaveformChartConfig::default(),
+    )
+}
+
+/// Like [`waveform_static_plotters_png_visualize`] but with additional configuration,
+/// e.g. the orientation of the y-axis. See [`WaveformChartConfig`].
+pub fn waveform_static_plotters_png_visualize_with_config(
+    samples: &[i16],
+    channels: Channels,
+    directory: &str,
+    filename: &str,
+    cfg: &WaveformChartConfig,
+) -> Vec<PathBuf> {
+    // each channel is drawn directly from the interleaved data; no copies
+    let channel_data = ChannelSamples::all(samples, channels);
+    if !channels.is_mono() {
+        return match cfg.stereo {
+            StereoHandling::SplitTwoFiles => channel_data
+                .iter()
+                .enumerate()
+                .map(|(channel, channel_samples)| {
+                    let path = Path::new(directory).join(format!(
+                        "{}{}",
+                        channel_file_prefix(channels, channel),
+                        filename
+                    ));
+                    draw_charts(&path, &[*channel_samples], cfg);
+                    path
+                })
+                .collect(),
+            StereoHandling::DownmixMono => {
+                let path = Path::new(directory).join(filename);
+                let downmixed = downmix(&channel_data);
+                draw_charts(&path, &[ChannelSamples::mono(&downmixed)], cfg);
+                vec![path]
+            }
+            StereoHandling::Stacked => {
+                let path = Path::new(directory).join(filename);
+                draw_charts(&path, &channel_data, cfg);
+                vec![path]
+            }
+        };
     }
 
     let mut path = PathBuf::new();
     path.push(directory);
     path.push(filename);
+    draw_charts(&path, &channel_data, cfg);
+    vec![path]
+}
 
-    let mut max = 0;
-    for sample in samples {
-        let sample = *sample as i32;
-        let sample = sample.abs();
-        if sample > max {
-            max = sample;
-        }
+/// Draws one chart per channel into a PNG file. The charts are stacked vertically.
+fn draw_charts<T: Copy + Into<f64>>(
+    path: &Path,
+    channel_data: &[ChannelSamples<T>],
+    cfg: &WaveformChartConfig,
+) {
+    let sample_count = channel_data
+        .iter()
+        .map(ChannelSamples::len)
+        .max()
+        .unwrap_or(0);
+    let width = (sample_count / 5) as u32;
+    let width = width.clamp(200, 4000);
+    let height = if channel_data.len() > 1 {
+        500 * channel_data.len() as u32
+    } else {
+        1000
+    };
+    let root = BitMapBackend::new(path, (width, height)).into_drawing_area();
+    root.fill(&WHITE).unwrap();
+    for (area, samples) in root
+        .split_evenly((channel_data.len(), 1))
+        .iter()
+        .zip(channel_data)
+    {
+        draw_chart(area, *samples, cfg);
     }
+}
 
-    let width = (samples.len() / 5) as u32;
-    let width = if width > 4000 { 4000 } else { width };
-    let root = BitMapBackend::new(&path, (width, 1000)).into_drawing_area();
-    root.fill(&WHITE).unwrap();
-    let mut chart = ChartBuilder::on(&root)
-        .caption("y=music(t)", ("sans-serif", 50).into_font())
+/// Draws the waveform of a single channel into the given drawing area.
+pub(crate) fn draw_chart<DB: DrawingBackend, T: Copy + Into<f64>>(
+    area: &DrawingArea<DB, Shift>,
+    samples: ChannelSamples<T>,
+    cfg: &WaveformChartConfig,
+) {
+    let max = samples.iter().map(f64::abs).fold(0.0, f64::max);
+
+    // silent or empty audio data: keep a valid y-axis
+    let max = max.max(1.0) as f32;
+    let y_range = if cfg.invert_y { max..-max } else { -max..max };
+    let font = cfg.font.as_deref().unwrap_or(DEFAULT_FONT);
+
+    let mut builder = ChartBuilder::on(area);
+    builder
         .margin(5)
         .x_label_area_size(30)
-        .y_label_area_size(30)
-        .build_cartesian_2d(0.0..samples.len() as f32, -max as f32..max as f32)
+        .y_label_area_size(30);
+    let title = cfg.title.unwrap_or("y=music(t)");
+    if !title.is_empty() {
+        builder.caption(
+            title,
+            (font, 50)
+                .into_font()
+                .color(&cfg.caption_color.unwrap_or(BLACK)),
+        );
+    }
+    let mut chart = builder
+        .build_cartesian_2d(0.0..samples.len() as f32, y_range)
         .unwrap();
 
-    chart.configure_mesh().draw().unwrap();
+    let mut mesh = chart.configure_mesh();
+    cfg.mesh.apply(&mut mesh);
+    // otherwise, "plotters" uses its default font for the labels
+    if cfg.font.is_some() {
+        mesh.label_style((font, 15));
+    }
+    mesh.draw().unwrap();
 
     chart
         .draw_series(LineSeries::new(
@@ -90,19 +202,35 @@ pub fn waveform_static_plotters_png_visualize(
             samples
                 .iter()
                 .enumerate()
-                .map(|(sample_i, amplitude)| (sample_i as f32, *amplitude as f32)),
+                .map(|(sample_i, amplitude)| (sample_i as f32, amplitude as f32)),
             &RED,
         ))
         .unwrap()
         // .label("y = music(t)")
         .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], RED));
 
-    chart
-        .configure_series_labels()
+    if cfg.draw_sample_dots {
+        chart
+            .draw_series(PointSeries::of_element(
+                samples
+                    .iter()
+                    .enumerate()
+                    .map(|(sample_i, amplitude)| (sample_i as f32, amplitude as f32)),
+                3,
+                RED.filled(),
+                &|coord, size, style| EmptyElement::at(coord) + Circle::new((0, 0), size, style),
+            ))
+            .unwrap();
+    }
+
+    let mut series_labels = chart.configure_series_labels();
+    series_labels
         .background_style(WHITE.mix(0.8))
-        .border_style(BLACK)
-        .draw()
-        .unwrap();
+        .border_style(BLACK);
+    if cfg.font.is_some() {
+        series_labels.label_font((font, 15));
+    }
+    series_labels.draw().unwrap();
 }
 
 #[cfg(test)]
@@ -113,6 +241,17 @@ mod tests {
     use minimp3::{Decoder as Mp3Decoder, Error as Mp3Error, Frame as Mp3Frame};
     use std::fs::File;
 
+    /// This test works, if it doesn't panic.
+    #[test]
+    fn test_empty_input() {
+        waveform_static_plotters_png_visualize(
+            &[],
+            Channels::Mono,
+            TEST_OUT_DIR,
+            "waveform_static_plotters_png_visualize_empty.png",
+        );
+    }
+
     #[test]
     fn test_visualize_png_output() {
         let mut path = PathBuf::new();
@@ -136,11 +275,117 @@ mod tests {
             }
         }
 
-        waveform_static_plotters_png_visualize(
+        let paths = waveform_static_plotters_png_visualize(
             &lrlr_mp3_samples,
             Channels::Stereo(ChannelInterleavement::LRLR),
             TEST_OUT_DIR,
             "waveform_static_plotters_png_visualize_example.png",
         );
+        let out_dir = PathBuf::from(TEST_OUT_DIR);
+        assert_eq!(
+            paths,
+            vec![
+                out_dir.join("left_waveform_static_plotters_png_visualize_example.png"),
+                out_dir.join("right_waveform_static_plotters_png_visualize_example.png"),
+            ]
+        );
+        assert!(paths.iter().all(|path| path.exists()));
+    }
+
+    #[test]
+    fn test_visualize_png_output_stacked() {
+        let left = (0..1000).map(|i| i * 32).collect::<Vec<i16>>();
+        let right = left.iter().map(|x| -x).collect::<Vec<_>>();
+        let paths = waveform_static_plotters_png_visualize_with_config(
+            &ChannelInterleavement::LRLR.from_channel_data(&left, &right),
+            Channels::Stereo(ChannelInterleavement::LRLR),
+            TEST_OUT_DIR,
+            "waveform_static_plotters_png_visualize_stacked.png",
+            &WaveformChartConfig {
+                stereo: StereoHandling::Stacked,
+                ..WaveformChartConfig::default()
+            },
+        );
+        assert_eq!(
+            paths,
+            vec![PathBuf::from(TEST_OUT_DIR)
+                .join("waveform_static_plotters_png_visualize_stacked.png")]
+        );
+    }
+
+    #[test]
+    fn test_visualize_png_output_invert_y() {
+        // a ramp from 0 to the maximum: rises to the top by default, falls if inverted
+        let samples = (0..1000).map(|i| i * 32).collect::<Vec<i16>>();
+        let paths = waveform_static_plotters_png_visualize(
+            &samples,
+            Channels::Mono,
+            TEST_OUT_DIR,
+            "waveform_static_plotters_png_visualize_ramp.png",
+        );
+        assert_eq!(paths.len(), 1);
+        assert!(paths[0].exists());
+        waveform_static_plotters_png_visualize_with_config(
+            &samples,
+            Channels::Mono,
+            TEST_OUT_DIR,
+            "waveform_static_plotters_png_visualize_ramp_inverted.png",
+            &WaveformChartConfig {
+                invert_y: true,
+                ..WaveformChartConfig::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_visualize_png_output_sample_dots() {
+        // 10 Hz sine sampled with 200 Hz
+        let samples = (0..40)
+            .map(|i| i as f32 / 200.0)
+            .map(|t| ((t * 10.0 * 2.0 * std::f32::consts::PI).sin() * i16::MAX as f32) as i16)
+            .collect::<Vec<_>>();
+        waveform_static_plotters_png_visualize_with_config(
+            &samples,
+            Channels::Mono,
+            TEST_OUT_DIR,
+            "waveform_static_plotters_png_visualize_sample_dots.png",
+            &WaveformChartConfig {
+                draw_sample_dots: true,
+                ..WaveformChartConfig::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_visualize_png_output_axis_only() {
+        let samples = (0..2000)
+            .map(|i| i as f32 / 1000.0)
+            .map(|t| ((t * 5.0 * 2.0 * std::f32::consts::PI).sin() * i16::MAX as f32) as i16)
+            .collect::<Vec<_>>();
+        waveform_static_plotters_png_visualize_with_config(
+            &samples,
+            Channels::Mono,
+            TEST_OUT_DIR,
+            "waveform_static_plotters_png_visualize_axis_only.png",
+            &WaveformChartConfig {
+                mesh: MeshMode::AxisOnly,
+                ..WaveformChartConfig::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_visualize_png_output_without_caption() {
+        let samples = (0..1000).map(|i| i * 32).collect::<Vec<i16>>();
+        waveform_static_plotters_png_visualize_with_config(
+            &samples,
+            Channels::Mono,
+            TEST_OUT_DIR,
+            "waveform_static_plotters_png_visualize_without_caption.png",
+            &WaveformChartConfig {
+                title: Some(""),
+                ..WaveformChartConfig::default()
+            },
+        );
     }
 }
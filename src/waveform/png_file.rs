@@ -23,77 +23,924 @@ SOFTWARE.
 */
 //! Static waveform visualization which exports the waveform to a PNG file.
 
-use crate::util::png::write_png_file_rgb_tuples;
-use crate::Channels;
-use std::path::PathBuf;
+use crate::error::VizError;
+use crate::util::colormap::ColorMap;
+use crate::util::dsp::sanitize_f32;
+use crate::util::png::{
+    try_write_png_file_rgb_tuples, try_write_png_file_rgb_tuples_transparent,
+    write_png_file_rgb_tuples_with_options, PngOptions,
+};
+use crate::waveform::onset::{detect_onsets, OnsetConfig};
+use crate::{ChannelGain, ChannelInterleavement, Channels};
+use std::path::{Path, PathBuf};
+
+/// How the area between the y-axis and the waveform is filled.
+#[derive(Debug, Copy, Clone, Default)]
+pub enum FillStyle {
+    /// No filling. Each sample is drawn as a single black pixel.
+    #[default]
+    None,
+    /// The area is filled with a solid color.
+    Solid((u8, u8, u8)),
+    /// The area is filled with a gradient. Pixels close to the y-axis get the `low`
+    /// color, pixels at the peak of the column get the `high` color.
+    Gradient {
+        low: (u8, u8, u8),
+        high: (u8, u8, u8),
+    },
+}
+
+impl FillStyle {
+    /// Returns the color for a pixel. `rel_distance` is the distance of the pixel to the
+    /// y-axis relative to the peak of its column, i.e. a value in interval `[0.0; 1.0]`.
+    fn color(&self, rel_distance: f64) -> (u8, u8, u8) {
+        match self {
+            Self::None => (0, 0, 0),
+            Self::Solid(color) => *color,
+            Self::Gradient { low, high } => {
+                let lerp = |low: u8, high: u8| {
+                    (low as f64 + (high as f64 - low as f64) * rel_distance).round() as u8
+                };
+                (
+                    lerp(low.0, high.0),
+                    lerp(low.1, high.1),
+                    lerp(low.2, high.2),
+                )
+            }
+        }
+    }
+}
+
+/// How the samples of the waveform are rendered. See [`WaveformPngConfig::render`].
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum WaveformRender {
+    /// Each sample is a pixel (or the area is filled, see [`FillStyle`]).
+    #[default]
+    Line,
+    /// Each pixel is colored by how many samples of its column fall into it, relative to the
+    /// most visited pixel of the column. Reveals the amplitude distribution of dense
+    /// waveforms with many periods per column, like an eye diagram. Pixels without samples
+    /// stay white. [`WaveformPngConfig::fill`] is ignored.
+    Density(ColorMap),
+}
+
+/// Configuration for [`waveform_static_png_visualize_with_config`].
+/// Use [`WaveformPngConfig::default`] and override the fields you need.
+#[derive(Debug, Clone, Default)]
+pub struct WaveformPngConfig {
+    /// How the area between the y-axis and the waveform is filled.
+    pub fill: FillStyle,
+    /// Fixed full-scale amplitude of the y-axis. Samples beyond it are clamped to the top or
+    /// bottom row. Useful to compare the loudness of several files on the same scale.
+    /// If `None`, [`i16::MAX`] is used.
+    pub y_max: Option<i16>,
+    /// Vertical lines in the given color at the given sample indices, e.g. to mark detected
+    /// onsets. For stereo, the index refers to the samples of one channel. Indices beyond the
+    /// audio data are ignored.
+    pub markers: Vec<(usize, (u8, u8, u8))>,
+    /// If `true`, negative sample values are drawn above the y-axis. By default, positive
+    /// values are drawn above it, like in
+    /// [`crate::waveform::plotters_png_file::waveform_static_plotters_png_visualize`].
+    pub invert_y: bool,
+    /// How stereo and multichannel audio data is visualized. Default is
+    /// [`StereoHandling::SplitTwoFiles`].
+    pub stereo: StereoHandling,
+    /// Options of the PNG encoding, e.g. the resolution for print or the compression. See
+    /// [`PngOptions`].
+    pub png: PngOptions,
+    /// How the samples are rendered. Default is [`WaveformRender::Line`].
+    pub render: WaveformRender,
+    /// Detects onsets (transients) in each channel and marks them like [`Self::markers`] in
+    /// [`ONSET_MARKER_COLOR`]. See [`detect_onsets`]. Default is `None`.
+    pub detect_onsets: Option<OnsetConfig>,
+    /// Writes RGBA files in which the white background is fully transparent and only the
+    /// waveform, the fill and the markers are opaque, e.g. to composite the waveform over a
+    /// video. Note that white fill or marker colors become transparent too. Default is
+    /// `false`.
+    pub transparent_background: bool,
+}
+
+/// Color of the background of the waveform images.
+const BACKGROUND: (u8, u8, u8) = (255, 255, 255);
+
+/// Color of the markers of [`WaveformPngConfig::detect_onsets`].
+pub const ONSET_MARKER_COLOR: (u8, u8, u8) = (255, 128, 0);
+
+/// How the waveform exporters visualize stereo (and multichannel) audio data.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum StereoHandling {
+    /// One file per channel, with "left_" and "right_" prefix for stereo and "channel_0_",
+    /// "channel_1_", ... prefix for more channels.
+    #[default]
+    SplitTwoFiles,
+    /// The channels are averaged into one waveform in a single file.
+    DownmixMono,
+    /// A single file in which each channel is drawn into its own horizontal strip.
+    Stacked,
+}
 
 /// Visualizes audio as a waveform in a png file in the most simple way.
 /// There are no axes. If the audio data is mono, it creates one file.
 /// If the data is stereo, it creates two files (with "left_" and "right_" prefix).
+/// For more channels, it creates one file per channel (with "channel_0_", "channel_1_", ...
+/// prefix).
+///
+/// Returns the paths of all written files: one for mono, left and right for stereo, and
+/// one per channel otherwise. See [`WaveformPngConfig::stereo`] for alternatives.
 pub fn waveform_static_png_visualize(
     samples: &[i16],
     channels: Channels,
     directory: &str,
     filename: &str,
-) {
+) -> Vec<PathBuf> {
+    waveform_static_png_visualize_with_config(
+        samples,
+        channels,
+        directory,
+        filename,
+        &WaveformPngConfig::default(),
+    )
+}
+
+/// Like [`waveform_static_png_visualize`] but with additional configuration,
+/// e.g. how the waveform is filled. See [`WaveformPngConfig`].
+pub fn waveform_static_png_visualize_with_config(
+    samples: &[i16],
+    channels: Channels,
+    directory: &str,
+    filename: &str,
+    cfg: &WaveformPngConfig,
+) -> Vec<PathBuf> {
+    let max_amplitude = cfg.y_max.unwrap_or(i16::MAX);
+    assert!(max_amplitude > 0, "y_max must be positive!");
+    waveform_static_png_visualize_generic(
+        samples,
+        max_amplitude as f64,
+        channels,
+        directory,
+        filename,
+        cfg,
+    )
+    .unwrap()
+}
+
+/// Like [`waveform_static_png_visualize_with_config`] but returns an error instead of
+/// panicking, e.g. if the file can't be written. See [`VizError`].
+///
+/// Unlike the panicking variants, empty audio data is an error too.
+pub fn try_waveform_static_png_visualize(
+    samples: &[i16],
+    channels: Channels,
+    directory: &str,
+    filename: &str,
+    cfg: &WaveformPngConfig,
+) -> Result<Vec<PathBuf>, VizError> {
+    if samples.is_empty() {
+        return Err(VizError::EmptyInput);
+    }
+    if !samples
+        .chunks_exact(channels.count())
+        .remainder()
+        .is_empty()
+    {
+        return Err(VizError::InvalidLength {
+            len: samples.len(),
+            channel_count: channels.count(),
+        });
+    }
+    let max_amplitude = cfg.y_max.unwrap_or(i16::MAX);
+    if max_amplitude <= 0 {
+        return Err(VizError::InvalidConfig("y_max must be positive!"));
+    }
+    if let Some(onset_cfg) = &cfg.detect_onsets {
+        onset_cfg.validate().map_err(VizError::InvalidConfig)?;
+    }
+    waveform_static_png_visualize_generic(
+        samples,
+        max_amplitude as f64,
+        channels,
+        directory,
+        filename,
+        cfg,
+    )
+}
+
+/// Like [`waveform_static_png_visualize`] but for audio data with a higher resolution
+/// than 16 bit, for example 24 bit audio in 32 bit containers.
+///
+/// `max_amplitude` is the value of a sample at full scale, i.e. `(1 << 23) - 1` for
+/// 24 bit audio or [`i32::MAX`] for 32 bit audio. It is used for the scaling of the y-axis.
+pub fn waveform_static_png_visualize_i32(
+    samples: &[i32],
+    max_amplitude: i32,
+    channels: Channels,
+    directory: &str,
+    filename: &str,
+) -> Vec<PathBuf> {
+    assert!(max_amplitude > 0, "max_amplitude must be positive!");
+    waveform_static_png_visualize_generic(
+        samples,
+        max_amplitude as f64,
+        channels,
+        directory,
+        filename,
+        &WaveformPngConfig::default(),
+    )
+    .unwrap()
+}
+
+/// Like [`waveform_static_png_visualize`] but for samples in interval `[-1.0; 1.0]`.
+///
+/// Non-finite samples (`NaN`, `+-Inf`) are replaced with a logged warning before they are
+/// drawn, see [`crate::util::dsp::sanitize_f32`].
+pub fn waveform_static_png_visualize_f32(
+    samples: &[f32],
+    channels: Channels,
+    directory: &str,
+    filename: &str,
+) -> Vec<PathBuf> {
+    waveform_static_png_visualize_generic(
+        &sanitize_f32(samples),
+        1.0,
+        channels,
+        directory,
+        filename,
+        &WaveformPngConfig::default(),
+    )
+    .unwrap()
+}
+
+/// Common implementation of the waveform visualization for all sample types.
+/// `max_amplitude` is the (absolute) value of a sample at full scale.
+/// Returns the paths of all written files or the first error.
+fn waveform_static_png_visualize_generic<T: Copy + Into<f64>>(
+    samples: &[T],
+    max_amplitude: f64,
+    channels: Channels,
+    directory: &str,
+    filename: &str,
+    cfg: &WaveformPngConfig,
+) -> Result<Vec<PathBuf>, VizError> {
     let image_width = 1500;
+    // height per channel for stacked channels
     let image_height = 200;
-    if channels.is_stereo() {
-        assert_eq!(
-            0,
-            samples.len() % 2,
-            "If stereo is provided, the length of the audio data must be even!"
+    if !channels.is_mono() && matches!(cfg.stereo, StereoHandling::SplitTwoFiles) {
+        // each channel is drawn directly from the interleaved data; no copies
+        return (0..channels.count())
+            .map(|channel| {
+                let mut image = vec![vec![BACKGROUND; image_width]; image_height];
+                draw_waveform(
+                    &mut image,
+                    ChannelSamples::new(samples, channels, channel),
+                    max_amplitude,
+                    cfg,
+                );
+                let mut path = PathBuf::new();
+                path.push(directory);
+                path.push(format!(
+                    "{}{}",
+                    channel_file_prefix(channels, channel),
+                    filename
+                ));
+                write_image(&path, &image, cfg)?;
+                Ok(path)
+            })
+            .collect();
+    }
+    let strip_count = match cfg.stereo {
+        StereoHandling::Stacked => channels.count(),
+        _ => 1,
+    };
+
+    let image = render_waveform_rgb_generic(
+        samples,
+        max_amplitude,
+        channels,
+        image_width,
+        image_height * strip_count,
+        cfg,
+    );
+
+    let mut path = PathBuf::new();
+    path.push(directory);
+    path.push(filename);
+    write_image(&path, &image, cfg)?;
+    Ok(vec![path])
+}
+
+/// Writes the image as PNG file with the resolution, the compression and the background
+/// of the configuration.
+fn write_image(
+    path: &Path,
+    image: &[Vec<(u8, u8, u8)>],
+    cfg: &WaveformPngConfig,
+) -> Result<(), png::EncodingError> {
+    if cfg.transparent_background {
+        try_write_png_file_rgb_tuples_transparent(path, image, BACKGROUND, &cfg.png)
+    } else {
+        try_write_png_file_rgb_tuples(path, image, &cfg.png)
+    }
+}
+
+/// Rasterizes the waveform into RGB image data (rows of RGB pixels) without writing a
+/// file, e.g. for benchmarks of the drawing or for custom encoders.
+///
+/// This is the drawing part of [`waveform_static_png_visualize_with_config`]. As there is
+/// only one image, stereo and multichannel audio data is downmixed for
+/// [`StereoHandling::DownmixMono`] and stacked otherwise. With stacked channels, each
+/// channel gets `height / channel count` rows; the last channel gets the remaining rows, so
+/// that the image always has `height` rows.
+pub fn render_waveform_rgb(
+    samples: &[i16],
+    channels: Channels,
+    width: usize,
+    height: usize,
+    cfg: &WaveformPngConfig,
+) -> Vec<Vec<(u8, u8, u8)>> {
+    let max_amplitude = cfg.y_max.unwrap_or(i16::MAX);
+    assert!(max_amplitude > 0, "y_max must be positive!");
+    render_waveform_rgb_generic(samples, max_amplitude as f64, channels, width, height, cfg)
+}
+
+/// Common implementation of [`render_waveform_rgb`] for all sample types.
+fn render_waveform_rgb_generic<T: Copy + Into<f64>>(
+    samples: &[T],
+    max_amplitude: f64,
+    channels: Channels,
+    width: usize,
+    height: usize,
+    cfg: &WaveformPngConfig,
+) -> Vec<Vec<(u8, u8, u8)>> {
+    assert!(
+        width > 0 && height >= channels.count(),
+        "the image must have at least one row per channel!"
+    );
+    if channels.is_mono() {
+        // RGB image data
+        let mut image = vec![vec![BACKGROUND; width]; height];
+        draw_waveform(
+            &mut image,
+            ChannelSamples::mono(samples),
+            max_amplitude,
+            cfg,
         );
-        let (left, right) = channels.stereo_interleavement().to_channel_data(samples);
-        waveform_static_png_visualize(
-            &left,
+        return image;
+    }
+
+    match cfg.stereo {
+        StereoHandling::DownmixMono => render_waveform_rgb_generic(
+            &downmix(&ChannelSamples::all(samples, channels)),
+            max_amplitude,
             Channels::Mono,
-            directory,
-            &format!("left_{}", filename),
+            width,
+            height,
+            cfg,
+        ),
+        StereoHandling::SplitTwoFiles | StereoHandling::Stacked => draw_stacked(
+            &ChannelSamples::all(samples, channels),
+            width,
+            height,
+            max_amplitude,
+            cfg,
+        ),
+    }
+}
+
+/// Visualizes multichannel audio as a waveform in a single png file. Each channel is
+/// drawn into its own horizontal strip. All strips share the same time axis.
+///
+/// The samples of all channels must alternate frame by frame, i.e. like
+/// [`crate::ChannelInterleavement::LRLR`] for stereo.
+///
+/// Returns the path of the written file.
+pub fn waveform_static_png_visualize_multichannel(
+    interleaved: &[i16],
+    channels: usize,
+    directory: &str,
+    filename: &str,
+) -> PathBuf {
+    waveform_static_png_visualize_multichannel_with_gains(
+        interleaved,
+        channels,
+        ChannelInterleavement::LRLR,
+        &[],
+        directory,
+        filename,
+        &WaveformPngConfig::default(),
+    )
+}
+
+/// Like [`waveform_static_png_visualize_multichannel`] but for any
+/// [`ChannelInterleavement`], with additional configuration (see [`WaveformPngConfig`]) and
+/// with a [`ChannelGain`] per channel.
+///
+/// `gains[i]` belongs to channel `i`; channels without an entry keep their level. A muted
+/// channel is rendered as a flat line. The gains are applied while the samples are drawn,
+/// so the audio data isn't copied. The channels are always stacked, i.e.
+/// [`WaveformPngConfig::stereo`] is ignored.
+///
+/// Returns the path of the written file.
+#[allow(clippy::too_many_arguments)]
+pub fn waveform_static_png_visualize_multichannel_with_gains(
+    interleaved: &[i16],
+    channels: usize,
+    interleavement: ChannelInterleavement,
+    gains: &[ChannelGain],
+    directory: &str,
+    filename: &str,
+    cfg: &WaveformPngConfig,
+) -> PathBuf {
+    let max_amplitude = cfg.y_max.unwrap_or(i16::MAX);
+    assert!(max_amplitude > 0, "y_max must be positive!");
+    let channel_data =
+        ChannelSamples::all(interleaved, Channels::from_count(channels, interleavement))
+            .into_iter()
+            .enumerate()
+            .map(|(channel, samples)| {
+                samples.with_gain(ChannelGain::for_channel(gains, channel).factor() as f64)
+            })
+            .collect::<Vec<_>>();
+
+    let image = draw_stacked(
+        &channel_data,
+        1500,
+        200 * channel_data.len(),
+        max_amplitude as f64,
+        cfg,
+    );
+
+    let mut path = PathBuf::new();
+    path.push(directory);
+    path.push(filename);
+    write_image(&path, &image, cfg).unwrap();
+    path
+}
+
+/// Prefix of the file name for the given channel with [`StereoHandling::SplitTwoFiles`]:
+/// "left_" and "right_" for stereo, "channel_0_", "channel_1_", ... for more channels.
+pub(crate) fn channel_file_prefix(channels: Channels, channel: usize) -> String {
+    match channels {
+        Channels::Mono => String::new(),
+        Channels::Stereo(_) if channel == 0 => String::from("left_"),
+        Channels::Stereo(_) => String::from("right_"),
+        Channels::Multi(..) => format!("channel_{}_", channel),
+    }
+}
+
+/// Averages the samples of all channels into a single channel, frame by frame.
+pub(crate) fn downmix<T: Copy + Into<f64>>(channel_data: &[ChannelSamples<T>]) -> Vec<f64> {
+    let len = channel_data
+        .iter()
+        .map(ChannelSamples::len)
+        .min()
+        .unwrap_or(0);
+    (0..len)
+        .map(|i| {
+            channel_data
+                .iter()
+                .map(|channel| channel.get(i))
+                .sum::<f64>()
+                / channel_data.len() as f64
+        })
+        .collect()
+}
+
+/// The samples of a single channel of (interleaved) audio data. Reads them directly from
+/// the audio data, so that the channels don't have to be copied into own vectors.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct ChannelSamples<'a, T> {
+    data: &'a [T],
+    /// Index of the first sample of the channel in `data`.
+    offset: usize,
+    /// Distance between two consecutive samples of the channel in `data`.
+    stride: usize,
+    len: usize,
+    /// Factor that is applied to each sample when it is read, e.g. of a [`ChannelGain`].
+    gain: f64,
+}
+
+impl<'a, T: Copy + Into<f64>> ChannelSamples<'a, T> {
+    /// All samples of mono audio data.
+    pub(crate) const fn mono(data: &'a [T]) -> Self {
+        Self {
+            data,
+            offset: 0,
+            stride: 1,
+            len: data.len(),
+            gain: 1.0,
+        }
+    }
+
+    /// The samples of the given channel. Panics, if the length of the audio data isn't a
+    /// multiple of the number of channels.
+    pub(crate) fn new(data: &'a [T], channels: Channels, channel: usize) -> Self {
+        let count = channels.count();
+        assert!(channel < count, "the channel doesn't exist!");
+        assert!(
+            data.chunks_exact(count).remainder().is_empty(),
+            "the length of the audio data must be a multiple of the number of channels!"
         );
-        waveform_static_png_visualize(
-            &right,
-            Channels::Mono,
-            directory,
-            &format!("right_{}", filename),
+        let len = data.len() / count;
+        let interleavement = match channels {
+            Channels::Mono => return Self::mono(data),
+            Channels::Stereo(interleavement) | Channels::Multi(_, interleavement) => interleavement,
+        };
+        if interleavement.is_lrlr() {
+            Self {
+                data,
+                offset: channel,
+                stride: count,
+                len,
+                gain: 1.0,
+            }
+        } else {
+            Self {
+                data,
+                offset: channel * len,
+                stride: 1,
+                len,
+                gain: 1.0,
+            }
+        }
+    }
+
+    /// The samples of all channels, in the order of the channels. Mono audio data results
+    /// in a single channel.
+    pub(crate) fn all(data: &'a [T], channels: Channels) -> Vec<Self> {
+        (0..channels.count())
+            .map(|channel| Self::new(data, channels, channel))
+            .collect()
+    }
+
+    /// The same samples, multiplied by `gain` when they are read.
+    pub(crate) const fn with_gain(self, gain: f64) -> Self {
+        Self { gain, ..self }
+    }
+
+    pub(crate) const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// The sample with the given index, multiplied by the gain.
+    pub(crate) fn get(&self, index: usize) -> f64 {
+        self.data[self.offset + index * self.stride].into() * self.gain
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = f64> + 'a {
+        let samples = *self;
+        (0..self.len).map(move |index| samples.get(index))
+    }
+}
+
+/// Draws each channel into its own horizontal strip of `image_height / channel count` pixels;
+/// the last strip gets the remaining rows. All strips share the same time axis and are
+/// separated by gray lines.
+fn draw_stacked<T: Copy + Into<f64>>(
+    channel_data: &[ChannelSamples<T>],
+    image_width: usize,
+    image_height: usize,
+    max_amplitude: f64,
+    cfg: &WaveformPngConfig,
+) -> Vec<Vec<(u8, u8, u8)>> {
+    let strip_height = image_height / channel_data.len();
+    // RGB image data
+    let mut image = vec![vec![BACKGROUND; image_width]; image_height];
+    // the last strip gets the remaining rows, if the height isn't a multiple of the strips
+    let (upper_strips, last_strip) = image.split_at_mut(strip_height * (channel_data.len() - 1));
+    let strips = upper_strips
+        .chunks_mut(strip_height)
+        .chain(std::iter::once(last_strip));
+    for (strip, samples) in strips.zip(channel_data.iter()) {
+        draw_waveform(strip, *samples, max_amplitude, cfg);
+    }
+    // separators between the strips
+    for row in image
+        .iter_mut()
+        .skip(strip_height)
+        .step_by(strip_height)
+        .take(channel_data.len() - 1)
+    {
+        row.iter_mut().for_each(|px| *px = (180, 180, 180));
+    }
+    image
+}
+
+/// Visualizes audio as a tiny waveform thumbnail (e.g. 200x60 pixels) in a png file.
+///
+/// Fast path for galleries with many clips: each column shows the min/max envelope of the
+/// samples that belong to it. There are no axes or labels. Stereo and multichannel audio
+/// is downmixed to one waveform.
+///
+/// Returns the path of the written file.
+pub fn waveform_thumbnail_png(
+    samples: &[i16],
+    channels: Channels,
+    width: usize,
+    height: usize,
+    directory: &str,
+    filename: &str,
+) -> PathBuf {
+    waveform_thumbnail_png_with_options(
+        samples,
+        channels,
+        width,
+        height,
+        directory,
+        filename,
+        &PngOptions::default(),
+    )
+}
+
+/// Like [`waveform_thumbnail_png`] but with the given [`PngOptions`], e.g. a resolution.
+pub fn waveform_thumbnail_png_with_options(
+    samples: &[i16],
+    channels: Channels,
+    width: usize,
+    height: usize,
+    directory: &str,
+    filename: &str,
+    png: &PngOptions,
+) -> PathBuf {
+    assert!(
+        width > 0 && height > 0,
+        "width and height must be positive!"
+    );
+    // one value per frame; the channels are read by their interleavement
+    let frames = downmix(&ChannelSamples::all(samples, channels));
+    let frame_count = frames.len();
+
+    let column_extents = (0..width)
+        .map(|x| {
+            // frames of this column; at least one frame, if there are fewer frames than columns
+            let begin = x * frame_count / width;
+            let end = ((x + 1) * frame_count / width)
+                .max(begin + 1)
+                .min(frame_count);
+            if begin >= end {
+                return None;
+            }
+            let (min, max) = frames[begin..end]
+                .iter()
+                .map(|x| *x as i32)
+                .fold((i32::MAX, i32::MIN), |(min, max), x| {
+                    (min.min(x), max.max(x))
+                });
+            Some((min, max))
+        })
+        .collect::<Vec<_>>();
+
+    // RGB image data
+    let mut image = vec![vec![BACKGROUND; width]; height];
+    draw_envelope(&mut image, &column_extents);
+
+    let mut path = PathBuf::new();
+    path.push(directory);
+    path.push(filename);
+    write_png_file_rgb_tuples_with_options(&path, &image, png);
+    path
+}
+
+/// Builds a waveform PNG incrementally from chunks of mono audio data. Useful for very
+/// long inputs that don't fit into memory.
+///
+/// Like [`waveform_thumbnail_png`], each column shows the min/max envelope of the samples
+/// that belong to it. Only the envelope is kept in memory, not the samples.
+///
+/// ```no_run
+/// use audio_visualizer::waveform::png_file::WaveformPngBuilder;
+/// use std::path::Path;
+///
+/// let mut builder = WaveformPngBuilder::new(1500, 200, 44100 * 3600);
+/// # let chunks: Vec<Vec<i16>> = vec![];
+/// for chunk in chunks {
+///     builder.push(&chunk);
+/// }
+/// builder.finish(Path::new("waveform.png"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct WaveformPngBuilder {
+    height: usize,
+    /// Expected number of samples in total. Determines which column a sample belongs to.
+    total_samples: u64,
+    /// Number of samples pushed so far.
+    pushed_samples: u64,
+    /// (min, max) of the samples per column.
+    column_extents: Vec<Option<(i32, i32)>>,
+}
+
+impl WaveformPngBuilder {
+    /// Creates a builder for an image of the given size. `total_samples` is the number of
+    /// samples that will be pushed in total. Samples beyond are added to the last column.
+    pub fn new(width: usize, height: usize, total_samples: u64) -> Self {
+        assert!(
+            width > 0 && height > 0,
+            "width and height must be positive!"
         );
-        return;
+        Self {
+            height,
+            total_samples: total_samples.max(1),
+            pushed_samples: 0,
+            column_extents: vec![None; width],
+        }
+    }
+
+    /// Adds the next chunk of mono audio data to the envelope.
+    pub fn push(&mut self, samples: &[i16]) {
+        let width = self.column_extents.len() as u64;
+        for sample in samples {
+            let x = (self.pushed_samples * width / self.total_samples).min(width - 1) as usize;
+            let sample = *sample as i32;
+            self.column_extents[x] = match self.column_extents[x] {
+                None => Some((sample, sample)),
+                Some((min, max)) => Some((min.min(sample), max.max(sample))),
+            };
+            self.pushed_samples += 1;
+        }
+    }
+
+    /// Draws the envelope and writes the PNG file.
+    pub fn finish(self, path: &Path) {
+        self.finish_with_options(path, &PngOptions::default())
+    }
+
+    /// Like [`Self::finish`] but with the given [`PngOptions`], e.g. a resolution.
+    pub fn finish_with_options(self, path: &Path, png: &PngOptions) {
+        // RGB image data
+        let mut image = vec![vec![BACKGROUND; self.column_extents.len()]; self.height];
+        draw_envelope(&mut image, &self.column_extents);
+        write_png_file_rgb_tuples_with_options(path, &image, png);
+    }
+}
+
+/// Draws the min/max envelope of 16 bit audio data into the given image. Each column gets
+/// a vertical line from its minimum to its maximum value. Columns without data stay empty.
+fn draw_envelope(image: &mut [Vec<(u8, u8, u8)>], column_extents: &[Option<(i32, i32)>]) {
+    let height = image.len();
+    let center = height / 2;
+    // y axis grows downwards
+    let to_y = |value: i32| {
+        let y = center as f64 - value as f64 / i16::MAX as f64 * (height / 2) as f64;
+        (y as usize).min(height - 1)
+    };
+    for (x, extent) in column_extents.iter().enumerate() {
+        if let Some((min, max)) = extent {
+            for row in image.iter_mut().take(to_y(*min) + 1).skip(to_y(*max)) {
+                row[x] = (0, 0, 0);
+            }
+        }
     }
+}
+
+/// Draws the waveform of mono audio data into the given image (rows of RGB pixels).
+/// The image can also be a horizontal strip of a bigger image.
+/// `max_amplitude` is the (absolute) value of a sample at full scale.
+fn draw_waveform<T: Copy + Into<f64>>(
+    image: &mut [Vec<(u8, u8, u8)>],
+    samples: ChannelSamples<T>,
+    max_amplitude: f64,
+    cfg: &WaveformPngConfig,
+) {
+    let image_height = image.len();
+    let image_width = image[0].len();
+
+    // If there are fewer samples than columns, the samples are linearly interpolated to one
+    // value per column. Otherwise, many columns get no sample and the waveform has gaps.
+    let upscaled = if samples.len() > 1 && samples.len() < image_width {
+        Some(interpolate_samples(samples, image_width))
+    } else {
+        None
+    };
+    let values: Box<dyn Iterator<Item = f64>> = match &upscaled {
+        Some(upscaled) => Box::new(upscaled.iter().copied()),
+        None => Box::new(samples.iter()),
+    };
+    let value_count = upscaled.as_ref().map_or(samples.len(), Vec::len);
 
     // needed for offset calculation; width per sample
-    let width_per_sample = image_width as f64 / samples.len() as f64;
+    let width_per_sample = image_width as f64 / value_count as f64;
     // height in pixel per possible value of a sample; counts in that the y axis lays in the middle
-    let height_per_max_amplitude = image_height as f64 / 2_f64 / i16::max_value() as f64;
+    let height_per_max_amplitude = image_height as f64 / 2_f64 / max_amplitude;
 
-    // RGB image data
-    let mut image = vec![vec![(255, 255, 255); image_width]; image_height];
-    for (sample_index, sample_value) in samples.iter().enumerate() {
+    // (min y, max y) of all samples per column
+    let mut column_extents: Vec<Option<(usize, usize)>> = vec![None; image_width];
+    // number of samples per pixel (row by row); only for WaveformRender::Density
+    let mut density = match cfg.render {
+        WaveformRender::Line => Vec::new(),
+        WaveformRender::Density(_) => vec![0_u32; image_width * image_height],
+    };
+    let mut prev_y = None;
+    for (sample_index, sample_value) in values.enumerate() {
         // x offset; from left
         let x = (sample_index as f64 * width_per_sample) as usize;
         // y offset; from top
         // image_height/2: there is our y-axis
-        let sample_value = *sample_value as f64 * -1.0; // y axis grows downwards
-        let mut y = ((image_height / 2) as f64 + sample_value * height_per_max_amplitude) as usize;
+        // y axis grows downwards; so positive values must be negated to be drawn above it
+        let sample_value = if cfg.invert_y {
+            sample_value
+        } else {
+            sample_value * -1.0
+        };
+        // negative values saturate to 0 during the cast
+        let y = ((image_height / 2) as f64 + sample_value * height_per_max_amplitude) as usize;
+        // due to rounding or samples beyond the full-scale amplitude it can happen that we get
+        // out of bounds
+        let y = y.min(image_height - 1);
 
-        // due to rounding it can happen that we get out of bounds
-        if y == image_height {
-            y -= 1;
+        if let WaveformRender::Density(_) = cfg.render {
+            density[y * image_width + x] += 1;
+        } else if matches!(cfg.fill, FillStyle::None) {
+            image[y][x] = (0, 0, 0);
+            // connect the interpolated values, so that steep edges have no gaps
+            if let (Some(_), Some(prev_y)) = (&upscaled, prev_y) {
+                for row in image.iter_mut().take(y.max(prev_y) + 1).skip(y.min(prev_y)) {
+                    row[x] = (0, 0, 0);
+                }
+            }
         }
+        prev_y = Some(y);
+        column_extents[x] = match column_extents[x] {
+            None => Some((y, y)),
+            Some((min_y, max_y)) => Some((min_y.min(y), max_y.max(y))),
+        };
+    }
 
-        image[y][x] = (0, 0, 0);
+    if let WaveformRender::Density(color_map) = cfg.render {
+        for x in 0..image_width {
+            let column_max = (0..image_height)
+                .map(|y| density[y * image_width + x])
+                .max()
+                .unwrap_or(0);
+            for (y, row) in image.iter_mut().enumerate() {
+                let count = density[y * image_width + x];
+                if count > 0 {
+                    row[x] = color_map.map(count as f32 / column_max as f32);
+                }
+            }
+        }
+    } else if !matches!(cfg.fill, FillStyle::None) {
+        // fill each column from the y-axis to the most distant sample
+        let center = image_height / 2;
+        for (x, extent) in column_extents.iter().enumerate() {
+            let (min_y, max_y) = match extent {
+                Some(extent) => *extent,
+                None => continue,
+            };
+            let (min_y, max_y) = (min_y.min(center), max_y.max(center));
+            let peak_distance = (center - min_y).max(max_y - center).max(1);
+            for (y, row) in image.iter_mut().enumerate().take(max_y + 1).skip(min_y) {
+                let distance = (y as i64 - center as i64).unsigned_abs() as usize;
+                row[x] = cfg.fill.color(distance as f64 / peak_distance as f64);
+            }
+        }
     }
 
-    let mut path = PathBuf::new();
-    path.push(directory);
-    path.push(filename);
-    write_png_file_rgb_tuples(&path, &image);
+    let onset_markers = cfg.detect_onsets.map_or_else(Vec::new, |onset_cfg| {
+        let samples = samples
+            .iter()
+            .map(|x| (x / max_amplitude) as f32)
+            .collect::<Vec<_>>();
+        detect_onsets(&samples, &onset_cfg)
+            .into_iter()
+            .map(|sample_index| (sample_index, ONSET_MARKER_COLOR))
+            .collect()
+    });
+    for (sample_index, color) in cfg.markers.iter().chain(onset_markers.iter()) {
+        if *sample_index >= samples.len() {
+            continue;
+        }
+        // same x mapping as for the samples above
+        let x = if upscaled.is_some() {
+            let x = *sample_index as f64 * (image_width - 1) as f64 / (samples.len() - 1) as f64;
+            x.round() as usize
+        } else {
+            (*sample_index as f64 * width_per_sample) as usize
+        };
+        for row in image.iter_mut() {
+            row[x] = *color;
+        }
+    }
+}
+
+/// Linearly interpolates the samples to `len` values. The first and the last value
+/// equal the first and the last sample. Requires at least two samples.
+fn interpolate_samples<T: Copy + Into<f64>>(samples: ChannelSamples<T>, len: usize) -> Vec<f64> {
+    let step = (samples.len() - 1) as f64 / (len - 1).max(1) as f64;
+    (0..len)
+        .map(|i| {
+            let pos = i as f64 * step;
+            let index = (pos as usize).min(samples.len() - 2);
+            let fraction = pos - index as f64;
+            let (a, b) = (samples.get(index), samples.get(index + 1));
+            a + (b - a) * fraction
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tests::testutil::sine::{sine_wave_audio_data, sine_wave_audio_data_multiple};
     use crate::tests::testutil::{TEST_OUT_DIR, TEST_SAMPLES_DIR};
-    use crate::ChannelInterleavement;
     use minimp3::{Decoder as Mp3Decoder, Error as Mp3Error, Frame as Mp3Frame};
     use std::fs::File;
 
@@ -101,12 +948,643 @@ mod tests {
     #[test]
     fn test_no_out_of_bounds_panic() {
         let audio_data = vec![i16::MAX, i16::MIN];
-        waveform_static_png_visualize(
+        let paths = waveform_static_png_visualize(
             &audio_data,
             Channels::Mono,
             TEST_OUT_DIR,
             "sample_1_waveform-test-out-of-bounds-check.png",
         );
+        assert_eq!(paths.len(), 1);
+        assert!(paths[0].exists());
+    }
+
+    /// This test works, if it doesn't panic.
+    #[test]
+    fn test_no_out_of_bounds_panic_i32() {
+        let audio_data = vec![i32::MAX, i32::MIN];
+        waveform_static_png_visualize_i32(
+            &audio_data,
+            i32::MAX,
+            Channels::Mono,
+            TEST_OUT_DIR,
+            "waveform-i32-test-out-of-bounds-check.png",
+        );
+    }
+
+    /// This test works, if it doesn't panic.
+    #[test]
+    fn test_non_finite_samples_f32() {
+        let audio_data = (0..1000)
+            .map(|i| (i as f32 / 50.0).sin())
+            .chain([f32::NAN, f32::INFINITY, f32::NEG_INFINITY, 2.0])
+            .collect::<Vec<_>>();
+        let paths = waveform_static_png_visualize_f32(
+            &audio_data,
+            Channels::Mono,
+            TEST_OUT_DIR,
+            "waveform-f32-test-non-finite-samples.png",
+        );
+        assert!(paths[0].exists());
+    }
+
+    /// This test works, if it doesn't panic.
+    #[test]
+    fn test_empty_input() {
+        waveform_static_png_visualize(
+            &[],
+            Channels::Mono,
+            TEST_OUT_DIR,
+            "waveform_static_png_visualize_empty.png",
+        );
+    }
+
+    #[test]
+    fn test_visualize_png_output_gradient_fill() {
+        let audio_data = sine_wave_audio_data_multiple(&[3.0, 50.0], 44100, 1000);
+        waveform_static_png_visualize_with_config(
+            &audio_data,
+            Channels::Mono,
+            TEST_OUT_DIR,
+            "waveform_static_png_visualize_gradient_example.png",
+            &WaveformPngConfig {
+                fill: FillStyle::Gradient {
+                    low: (0, 40, 120),
+                    high: (120, 220, 255),
+                },
+                ..WaveformPngConfig::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_visualize_png_output_markers() {
+        let audio_data = sine_wave_audio_data(3.0, 44100, 1000);
+        let cfg = WaveformPngConfig {
+            // at the zero crossings of the sine wave
+            markers: vec![
+                (0, (255, 0, 0)),
+                (44100 / 6, (0, 160, 0)),
+                (44100 / 3, (0, 0, 255)),
+                (audio_data.len(), (255, 0, 0)),
+            ],
+            ..WaveformPngConfig::default()
+        };
+        waveform_static_png_visualize_with_config(
+            &audio_data,
+            Channels::Mono,
+            TEST_OUT_DIR,
+            "waveform_static_png_visualize_markers_example.png",
+            &cfg,
+        );
+        // 100 samples on 1500 pixels
+        waveform_static_png_visualize_with_config(
+            &audio_data[..100],
+            Channels::Mono,
+            TEST_OUT_DIR,
+            "waveform_static_png_visualize_markers_upscaled_example.png",
+            &WaveformPngConfig {
+                markers: vec![(0, (255, 0, 0)), (99, (0, 0, 255))],
+                ..WaveformPngConfig::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_visualize_png_output_onsets() {
+        // three decaying 50 Hz "hits"
+        let hit = sine_wave_audio_data(50.0, 44100, 200)
+            .iter()
+            .enumerate()
+            .map(|(i, x)| (*x as f32 * (-(i as f32) / 1000.0).exp()) as i16)
+            .collect::<Vec<_>>();
+        let mut audio_data = vec![0; 4096];
+        for _ in 0..3 {
+            audio_data.extend_from_slice(&hit);
+            audio_data.extend_from_slice(&[0; 4096]);
+        }
+        let image = render_waveform_rgb(
+            &audio_data,
+            Channels::Mono,
+            1500,
+            200,
+            &WaveformPngConfig {
+                detect_onsets: Some(OnsetConfig::default()),
+                ..WaveformPngConfig::default()
+            },
+        );
+        let onset_columns = image[0]
+            .iter()
+            .filter(|pixel| **pixel == ONSET_MARKER_COLOR)
+            .count();
+        assert_eq!(onset_columns, 3);
+        waveform_static_png_visualize_with_config(
+            &audio_data,
+            Channels::Mono,
+            TEST_OUT_DIR,
+            "waveform_static_png_visualize_onsets_example.png",
+            &WaveformPngConfig {
+                detect_onsets: Some(OnsetConfig::default()),
+                ..WaveformPngConfig::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_visualize_png_output_transparent_background() {
+        let audio_data = sine_wave_audio_data(3.0, 44100, 1000);
+        let paths = try_waveform_static_png_visualize(
+            &audio_data,
+            Channels::Mono,
+            TEST_OUT_DIR,
+            "waveform_static_png_visualize_transparent_example.png",
+            &WaveformPngConfig {
+                fill: FillStyle::Solid((0, 0, 255)),
+                transparent_background: true,
+                ..WaveformPngConfig::default()
+            },
+        )
+        .unwrap();
+        let file = std::fs::File::open(&paths[0]).unwrap();
+        let mut reader = png::Decoder::new(file).read_info().unwrap();
+        let mut buf = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut buf).unwrap();
+        let alphas = buf
+            .chunks_exact(4)
+            .map(|pixel| pixel[3])
+            .collect::<Vec<_>>();
+        assert!(alphas.contains(&0));
+        assert!(alphas.contains(&255));
+    }
+
+    #[test]
+    fn test_visualize_png_output_fixed_y_max() {
+        // peaks at half of the full scale; samples beyond y_max are clamped
+        let audio_data = sine_wave_audio_data(3.0, 44100, 1000)
+            .iter()
+            .map(|x| x / 2)
+            .collect::<Vec<_>>();
+        for (y_max, filename) in [
+            (
+                i16::MAX,
+                "waveform_static_png_visualize_y_max_full_scale.png",
+            ),
+            (
+                i16::MAX / 4,
+                "waveform_static_png_visualize_y_max_clamped.png",
+            ),
+        ] {
+            waveform_static_png_visualize_with_config(
+                &audio_data,
+                Channels::Mono,
+                TEST_OUT_DIR,
+                filename,
+                &WaveformPngConfig {
+                    y_max: Some(y_max),
+                    ..WaveformPngConfig::default()
+                },
+            );
+        }
+    }
+
+    #[test]
+    fn test_draw_waveform_invert_y() {
+        // a single positive full-scale value is drawn at the top, if not inverted
+        for (invert_y, expected_row) in [(false, 0), (true, 9)] {
+            let mut image = vec![vec![(255, 255, 255); 1]; 10];
+            draw_waveform(
+                &mut image,
+                ChannelSamples::mono(&[i16::MAX]),
+                i16::MAX as f64,
+                &WaveformPngConfig {
+                    invert_y,
+                    ..WaveformPngConfig::default()
+                },
+            );
+            assert_eq!(image[expected_row][0], (0, 0, 0));
+        }
+    }
+
+    #[test]
+    fn test_visualize_png_output_stereo_handling() {
+        let left = sine_wave_audio_data(3.0, 44100, 1000);
+        let right = sine_wave_audio_data(5.0, 44100, 1000);
+        let lrlr = ChannelInterleavement::LRLR.from_channel_data(&left, &right);
+        for (stereo, filename) in [
+            (
+                StereoHandling::DownmixMono,
+                "waveform_static_png_visualize_stereo_downmix.png",
+            ),
+            (
+                StereoHandling::Stacked,
+                "waveform_static_png_visualize_stereo_stacked.png",
+            ),
+        ] {
+            let paths = waveform_static_png_visualize_with_config(
+                &lrlr,
+                Channels::Stereo(ChannelInterleavement::LRLR),
+                TEST_OUT_DIR,
+                filename,
+                &WaveformPngConfig {
+                    stereo,
+                    ..WaveformPngConfig::default()
+                },
+            );
+            assert_eq!(paths, vec![PathBuf::from(TEST_OUT_DIR).join(filename)]);
+        }
+    }
+
+    #[test]
+    fn test_try_waveform_static_png_visualize() {
+        let cfg = WaveformPngConfig::default();
+        let stereo = Channels::Stereo(ChannelInterleavement::LRLR);
+        assert!(matches!(
+            try_waveform_static_png_visualize(&[], Channels::Mono, TEST_OUT_DIR, "x.png", &cfg),
+            Err(VizError::EmptyInput)
+        ));
+        assert!(matches!(
+            try_waveform_static_png_visualize(&[1, 2, 3], stereo, TEST_OUT_DIR, "x.png", &cfg),
+            Err(VizError::InvalidLength {
+                len: 3,
+                channel_count: 2
+            })
+        ));
+        assert!(matches!(
+            try_waveform_static_png_visualize(
+                &[1, 2],
+                Channels::Mono,
+                "test/out/does/not/exist",
+                "x.png",
+                &cfg
+            ),
+            Err(VizError::Io(_))
+        ));
+        assert!(matches!(
+            try_waveform_static_png_visualize(
+                &[1, 2],
+                Channels::Mono,
+                TEST_OUT_DIR,
+                "x.png",
+                &WaveformPngConfig {
+                    y_max: Some(0),
+                    ..WaveformPngConfig::default()
+                }
+            ),
+            Err(VizError::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            try_waveform_static_png_visualize(
+                &[1, 2],
+                Channels::Mono,
+                TEST_OUT_DIR,
+                "x.png",
+                &WaveformPngConfig {
+                    detect_onsets: Some(OnsetConfig {
+                        window_len: 0,
+                        ..OnsetConfig::default()
+                    }),
+                    ..WaveformPngConfig::default()
+                }
+            ),
+            Err(VizError::InvalidConfig("window_len must be positive!"))
+        ));
+        let paths = try_waveform_static_png_visualize(
+            &sine_wave_audio_data(3.0, 44100, 1000),
+            Channels::Mono,
+            TEST_OUT_DIR,
+            "try_waveform_static_png_visualize_example.png",
+            &cfg,
+        )
+        .unwrap();
+        assert_eq!(paths.len(), 1);
+    }
+
+    #[test]
+    fn test_render_waveform_rgb() {
+        let samples = sine_wave_audio_data(3.0, 44100, 1000);
+        let image = render_waveform_rgb(
+            &samples,
+            Channels::Mono,
+            300,
+            100,
+            &WaveformPngConfig::default(),
+        );
+        assert_eq!(image.len(), 100);
+        assert!(image.iter().all(|row| row.len() == 300));
+        assert!(image.iter().flatten().any(|px| *px == (0, 0, 0)));
+
+        // one strip per channel
+        let lrlr = ChannelInterleavement::LRLR.from_channel_data(&samples, &samples);
+        let image = render_waveform_rgb(
+            &lrlr,
+            Channels::Stereo(ChannelInterleavement::LRLR),
+            300,
+            101,
+            &WaveformPngConfig::default(),
+        );
+        // the last strip gets the remaining row
+        assert_eq!(image.len(), 101);
+        assert!(image[50].iter().all(|px| *px == (180, 180, 180)));
+        assert_eq!(
+            image
+                .iter()
+                .filter(|row| row.iter().all(|px| *px == (180, 180, 180)))
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_render_waveform_rgb_density() {
+        // many periods per column: a sine stays longest near its peaks
+        let samples = sine_wave_audio_data(1000.0, 44100, 1000);
+        let cfg = WaveformPngConfig {
+            render: WaveformRender::Density(ColorMap::Viridis),
+            ..WaveformPngConfig::default()
+        };
+        let image = render_waveform_rgb(&samples, Channels::Mono, 100, 100, &cfg);
+        let densest = ColorMap::Viridis.map(1.0);
+        let densest_rows = (0..100)
+            .filter(|y| image[*y][50] == densest)
+            .collect::<Vec<_>>();
+        assert!(!densest_rows.is_empty());
+        // the amplitude of the test data is 0.6, i.e. the peaks are at rows 20 and 79
+        assert!(densest_rows
+            .iter()
+            .all(|y| (18..=21).contains(y) || (78..=81).contains(y)));
+
+        waveform_static_png_visualize_with_config(
+            &samples,
+            Channels::Mono,
+            TEST_OUT_DIR,
+            "waveform_static_png_visualize_density.png",
+            &cfg,
+        );
+    }
+
+    #[test]
+    fn test_downmix() {
+        assert_eq!(
+            downmix(&[
+                ChannelSamples::mono(&[1_i16, -4]),
+                ChannelSamples::mono(&[3, 4]),
+                ChannelSamples::mono(&[2, 3]),
+            ]),
+            vec![2.0, 1.0]
+        );
+    }
+
+    #[test]
+    fn test_visualize_png_output_thumbnail() {
+        let audio_data = sine_wave_audio_data_multiple(&[3.0, 50.0], 44100, 2000);
+        let path = waveform_thumbnail_png(
+            &audio_data,
+            Channels::Mono,
+            200,
+            60,
+            TEST_OUT_DIR,
+            "waveform_thumbnail_png_example.png",
+        );
+        assert!(path.exists());
+
+        // fewer samples than columns
+        waveform_thumbnail_png(
+            &[i16::MAX, i16::MIN, 0, i16::MIN],
+            Channels::Stereo(ChannelInterleavement::LRLR),
+            200,
+            60,
+            TEST_OUT_DIR,
+            "waveform_thumbnail_png_short.png",
+        );
+    }
+
+    #[test]
+    fn test_thumbnail_interleavement() {
+        let left = sine_wave_audio_data_multiple(&[3.0], 44100, 2000);
+        let right = sine_wave_audio_data_multiple(&[50.0], 44100, 2000);
+        let files = [ChannelInterleavement::LRLR, ChannelInterleavement::LLRR]
+            .iter()
+            .map(|interleavement| {
+                let path = waveform_thumbnail_png(
+                    &interleavement.from_channel_data(&left, &right),
+                    Channels::Stereo(*interleavement),
+                    200,
+                    60,
+                    TEST_OUT_DIR,
+                    &format!("waveform_thumbnail_png_{:?}.png", interleavement),
+                );
+                std::fs::read(path).unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(files[0], files[1]);
+
+        // the channels of a multichannel LLRR signal are blocks, too
+        let center = sine_wave_audio_data_multiple(&[10.0], 44100, 2000);
+        let lrlr = (0..left.len())
+            .flat_map(|i| vec![left[i], right[i], center[i]])
+            .collect::<Vec<_>>();
+        let llrr = [left, right, center].concat();
+        let files = [
+            (lrlr, ChannelInterleavement::LRLR),
+            (llrr, ChannelInterleavement::LLRR),
+        ]
+        .iter()
+        .map(|(samples, interleavement)| {
+            let path = waveform_thumbnail_png(
+                samples,
+                Channels::Multi(3, *interleavement),
+                200,
+                60,
+                TEST_OUT_DIR,
+                &format!("waveform_thumbnail_png_multi_{:?}.png", interleavement),
+            );
+            std::fs::read(path).unwrap()
+        })
+        .collect::<Vec<_>>();
+        assert_eq!(files[0], files[1]);
+    }
+
+    #[test]
+    fn test_waveform_png_builder() {
+        let audio_data = sine_wave_audio_data_multiple(&[3.0, 50.0], 44100, 2000);
+        let mut builder = WaveformPngBuilder::new(1500, 200, audio_data.len() as u64);
+        for chunk in audio_data.chunks(1000) {
+            builder.push(chunk);
+        }
+        let path = PathBuf::from(TEST_OUT_DIR).join("waveform_png_builder_example.png");
+        builder.finish(&path);
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn test_channel_samples_match_multichannel_data() {
+        let samples = (0..12).collect::<Vec<i16>>();
+        for channels in [
+            Channels::Mono,
+            Channels::Stereo(ChannelInterleavement::LRLR),
+            Channels::Stereo(ChannelInterleavement::LLRR),
+            Channels::Multi(3, ChannelInterleavement::LRLR),
+            Channels::Multi(3, ChannelInterleavement::LLRR),
+        ] {
+            let channel_data = match channels {
+                Channels::Mono => vec![samples.clone()],
+                Channels::Stereo(interleavement) => {
+                    interleavement.to_multichannel_data(&samples, 2)
+                }
+                Channels::Multi(count, interleavement) => {
+                    interleavement.to_multichannel_data(&samples, count)
+                }
+            };
+            let all = ChannelSamples::all(&samples, channels);
+            assert_eq!(all.len(), channel_data.len());
+            for (channel_samples, expected) in all.iter().zip(channel_data.iter()) {
+                assert_eq!(channel_samples.len(), expected.len());
+                let expected = expected.iter().map(|x| *x as f64).collect::<Vec<_>>();
+                assert_eq!(channel_samples.iter().collect::<Vec<_>>(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_interpolate_samples() {
+        assert_eq!(
+            interpolate_samples(ChannelSamples::mono(&[0_i16, 100, -100]), 5),
+            vec![0.0, 50.0, 100.0, 0.0, -100.0]
+        );
+    }
+
+    #[test]
+    fn test_visualize_png_output_upscaled() {
+        // 100 samples on 1500 pixels
+        let audio_data = sine_wave_audio_data(10.0, 1000, 100);
+        waveform_static_png_visualize(
+            &audio_data,
+            Channels::Mono,
+            TEST_OUT_DIR,
+            "waveform_static_png_visualize_upscaled_example.png",
+        );
+    }
+
+    #[test]
+    fn test_fill_style_gradient_color() {
+        let fill = FillStyle::Gradient {
+            low: (0, 100, 200),
+            high: (200, 100, 0),
+        };
+        assert_eq!(fill.color(0.0), (0, 100, 200));
+        assert_eq!(fill.color(0.5), (100, 100, 100));
+        assert_eq!(fill.color(1.0), (200, 100, 0));
+    }
+
+    #[test]
+    fn test_visualize_png_output_24bit() {
+        const MAX_24BIT: i32 = (1 << 23) - 1;
+        // 24 bit sine wave in 32 bit containers
+        let audio_data = (0..44100)
+            .map(|i| i as f64 / 44100.0)
+            .map(|t| (t * 10.0 * 2.0 * std::f64::consts::PI).sin())
+            .map(|x| (x * MAX_24BIT as f64 * 0.8) as i32)
+            .collect::<Vec<_>>();
+        waveform_static_png_visualize_i32(
+            &audio_data,
+            MAX_24BIT,
+            Channels::Mono,
+            TEST_OUT_DIR,
+            "waveform_static_png_visualize_24bit_example.png",
+        );
+    }
+
+    #[test]
+    fn test_visualize_png_output_multichannel() {
+        // 6 channels with sine waves of different frequencies
+        let channel_data = [10.0, 20.0, 30.0, 40.0, 50.0, 60.0]
+            .iter()
+            .map(|frequency| sine_wave_audio_data(*frequency, 44100, 1000))
+            .collect::<Vec<_>>();
+        let interleaved = (0..channel_data[0].len())
+            .flat_map(|i| channel_data.iter().map(move |channel| channel[i]))
+            .collect::<Vec<_>>();
+
+        waveform_static_png_visualize_multichannel(
+            &interleaved,
+            channel_data.len(),
+            TEST_OUT_DIR,
+            "waveform_static_png_visualize_multichannel_example.png",
+        );
+
+        // focus on the third channel: mute the first two, attenuate the others
+        let mut gains = vec![
+            ChannelGain {
+                gain: 1.0,
+                muted: true,
+            };
+            2
+        ];
+        gains.push(ChannelGain::default());
+        gains.push(ChannelGain {
+            gain: 0.25,
+            muted: false,
+        });
+        waveform_static_png_visualize_multichannel_with_gains(
+            &interleaved,
+            channel_data.len(),
+            ChannelInterleavement::LRLR,
+            &gains,
+            TEST_OUT_DIR,
+            "waveform_static_png_visualize_multichannel_gains_example.png",
+            &WaveformPngConfig::default(),
+        );
+    }
+
+    #[test]
+    fn test_visualize_multichannel_llrr_and_options() {
+        let channel_data = [10.0, 20.0, 30.0]
+            .iter()
+            .map(|frequency| sine_wave_audio_data(*frequency, 44100, 1000))
+            .collect::<Vec<_>>();
+        let lrlr = (0..channel_data[0].len())
+            .flat_map(|i| channel_data.iter().map(move |channel| channel[i]))
+            .collect::<Vec<_>>();
+        let llrr = channel_data.concat();
+        let cfg = WaveformPngConfig {
+            png: PngOptions {
+                dpi: Some(300),
+                ..PngOptions::default()
+            },
+            ..WaveformPngConfig::default()
+        };
+        let gains = [ChannelGain {
+            gain: 0.5,
+            muted: false,
+        }];
+
+        let read = |interleaved: &[i16], interleavement, filename| {
+            let path = waveform_static_png_visualize_multichannel_with_gains(
+                interleaved,
+                3,
+                interleavement,
+                &gains,
+                TEST_OUT_DIR,
+                filename,
+                &cfg,
+            );
+            std::fs::read(path).unwrap()
+        };
+        let lrlr_png = read(
+            &lrlr,
+            ChannelInterleavement::LRLR,
+            "waveform_static_png_visualize_multichannel_lrlr.png",
+        );
+        let llrr_png = read(
+            &llrr,
+            ChannelInterleavement::LLRR,
+            "waveform_static_png_visualize_multichannel_llrr.png",
+        );
+        assert_eq!(lrlr_png, llrr_png);
+
+        let reader = png::Decoder::new(lrlr_png.as_slice()).read_info().unwrap();
+        let pixel_dims = reader.info().pixel_dims.unwrap();
+        // 300 dpi = 11811 pixels per meter
+        assert_eq!((pixel_dims.xppu, pixel_dims.yppu), (11811, 11811));
     }
 
     #[test]
@@ -132,11 +1610,19 @@ mod tests {
             }
         }
 
-        waveform_static_png_visualize(
+        let paths = waveform_static_png_visualize(
             &lrlr_mp3_samples,
             Channels::Stereo(ChannelInterleavement::LRLR),
             TEST_OUT_DIR,
             "waveform_static_png_visualize_example.png",
         );
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from(TEST_OUT_DIR).join("left_waveform_static_png_visualize_example.png"),
+                PathBuf::from(TEST_OUT_DIR).join("right_waveform_static_png_visualize_example.png"),
+            ]
+        );
+        assert!(paths.iter().all(|path| path.exists()));
     }
 }
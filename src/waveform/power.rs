@@ -0,0 +1,187 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Static energy-over-time (power envelope) visualization which exports to a PNG file.
+//! It is the static counterpart of the `live_visualize_signal_power` example.
+
+use crate::util::DEFAULT_FONT;
+use plotters::prelude::*;
+use std::path::PathBuf;
+
+/// Calculates the mean power (mean of the squared samples) of consecutive windows of
+/// `window_ms` milliseconds. The last window may be shorter.
+///
+/// Returns pairs of `(start time of window in seconds, mean power)`.
+pub fn power_envelope(samples: &[f32], sample_rate: u32, window_ms: u32) -> Vec<(f32, f32)> {
+    map_windows(samples, sample_rate, window_ms, mean_power)
+}
+
+/// Mean power of a window of samples, i.e. the mean of the squared samples.
+pub(crate) fn mean_power(window: &[f32]) -> f32 {
+    window.iter().map(|x| x * x).sum::<f32>() / window.len() as f32
+}
+
+/// Splits the samples into consecutive windows of `window_ms` milliseconds and applies
+/// `f` to each window. The last window may be shorter.
+///
+/// Returns pairs of `(start time of window in seconds, f(window))`.
+pub(crate) fn map_windows(
+    samples: &[f32],
+    sample_rate: u32,
+    window_ms: u32,
+    f: impl Fn(&[f32]) -> f32,
+) -> Vec<(f32, f32)> {
+    assert!(window_ms > 0, "window_ms must be positive!");
+    let window_len = ((sample_rate as u64 * window_ms as u64 / 1000) as usize).max(1);
+    map_windows_of_len(samples, window_len, f)
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let time = (i * window_len) as f32 / sample_rate as f32;
+            (time, value)
+        })
+        .collect()
+}
+
+/// Like [`map_windows`] but with windows of `window_len` samples. Returns `f(window)` of
+/// each window.
+pub(crate) fn map_windows_of_len(
+    samples: &[f32],
+    window_len: usize,
+    f: impl Fn(&[f32]) -> f32,
+) -> Vec<f32> {
+    assert!(window_len > 0, "window_len must be positive!");
+    samples.chunks(window_len).map(f).collect()
+}
+
+/// Visualizes the power envelope (see [`power_envelope`]) of mono audio data in a PNG file
+/// using the "plotters" crate, i.e. the mean power per window over time.
+///
+/// # Parameters
+/// - `samples` Mono audio samples in interval `[-1.0; 1.0]`.
+/// - `sample_rate` Sampling rate of the audio data, e.g. 44100.
+/// - `window_ms` Length of each window in milliseconds, e.g. 10.
+/// - `directory` Directory of the PNG file.
+/// - `filename` Name of the PNG file.
+pub fn power_envelope_png_visualize(
+    samples: &[f32],
+    sample_rate: u32,
+    window_ms: u32,
+    directory: &str,
+    filename: &str,
+) {
+    let envelope = power_envelope(samples, sample_rate, window_ms);
+    let duration = samples.len() as f32 / sample_rate as f32;
+    draw_over_time(
+        envelope,
+        duration,
+        "power envelope",
+        "mean power",
+        directory,
+        filename,
+    );
+}
+
+/// Draws `(time in seconds, value)` pairs as line chart into a PNG file. The x-axis spans
+/// `duration` seconds; the y-axis starts at zero.
+pub(crate) fn draw_over_time(
+    points: Vec<(f32, f32)>,
+    duration: f32,
+    caption: &str,
+    y_desc: &str,
+    directory: &str,
+    filename: &str,
+) {
+    let mut path = PathBuf::new();
+    path.push(directory);
+    path.push(filename);
+
+    let max = points
+        .iter()
+        .map(|(_, value)| *value)
+        .fold(0.0, f32::max)
+        // silent or empty audio data: keep a valid y-axis
+        .max(f32::EPSILON);
+
+    let root = BitMapBackend::new(&path, (1500, 700)).into_drawing_area();
+    root.fill(&WHITE).unwrap();
+    let mut chart = ChartBuilder::on(&root)
+        .caption(caption, (DEFAULT_FONT, 20).into_font())
+        .margin(5)
+        .x_label_area_size(60)
+        .y_label_area_size(60)
+        .build_cartesian_2d(0.0..duration.max(f32::EPSILON), 0.0..max)
+        .unwrap();
+
+    chart
+        .configure_mesh()
+        .x_desc("time (seconds)")
+        .y_desc(y_desc)
+        .draw()
+        .unwrap();
+
+    chart.draw_series(LineSeries::new(points, &RED)).unwrap();
+
+    root.present().unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::testutil::TEST_OUT_DIR;
+
+    #[test]
+    fn test_power_envelope() {
+        // full scale sine wave has a power of 0.5; silence has a power of zero
+        let mut samples = (0..44100)
+            .map(|i| i as f32 / 44100.0)
+            .map(|t| (t * 441.0 * 2.0 * std::f32::consts::PI).sin())
+            .collect::<Vec<_>>();
+        samples.extend(vec![0.0; 44100]);
+
+        let envelope = power_envelope(&samples, 44100, 100);
+        assert_eq!(envelope.len(), 20);
+        assert_eq!(envelope[1].0, 0.1);
+        assert!((envelope[0].1 - 0.5).abs() < 0.01);
+        assert_eq!(envelope[19].1, 0.0);
+    }
+
+    #[test]
+    fn test_power_envelope_png_visualize() {
+        // fade in and fade out
+        let samples = (0..44100 * 2)
+            .map(|i| i as f32 / 44100.0)
+            .map(|t| {
+                (t * 440.0 * 2.0 * std::f32::consts::PI).sin()
+                    * (t * std::f32::consts::PI / 2.0).sin()
+            })
+            .collect::<Vec<_>>();
+        power_envelope_png_visualize(
+            &samples,
+            44100,
+            10,
+            TEST_OUT_DIR,
+            "power_envelope_png_visualize_fade_example.png",
+        );
+    }
+}
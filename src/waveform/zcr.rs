@@ -0,0 +1,114 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+//! Static zero-crossing rate (ZCR) visualization which exports to a PNG file. The ZCR is
+//! high for noisy (unvoiced) parts of a signal and low for tonal (voiced) parts.
+
+use crate::waveform::power::{draw_over_time, map_windows};
+
+/// Calculates the zero-crossing rate of consecutive windows of `window_ms` milliseconds,
+/// i.e. the number of sign changes per second. The last window may be shorter.
+///
+/// Zero counts as positive. Sign changes between two windows are not counted.
+///
+/// Returns pairs of `(start time of window in seconds, zero crossings per second)`.
+pub fn zero_crossing_rate(samples: &[f32], sample_rate: u32, window_ms: u32) -> Vec<(f32, f32)> {
+    map_windows(samples, sample_rate, window_ms, |window| {
+        let crossings = window
+            .windows(2)
+            .filter(|pair| (pair[0] >= 0.0) != (pair[1] >= 0.0))
+            .count();
+        crossings as f32 * sample_rate as f32 / window.len() as f32
+    })
+}
+
+/// Visualizes the zero-crossing rate (see [`zero_crossing_rate`]) of mono audio data in a
+/// PNG file using the "plotters" crate, i.e. the zero crossings per second over time.
+///
+/// # Parameters
+/// - `samples` Mono audio samples in interval `[-1.0; 1.0]`.
+/// - `sample_rate` Sampling rate of the audio data, e.g. 44100.
+/// - `window_ms` Length of each window in milliseconds, e.g. 20.
+/// - `directory` Directory of the PNG file.
+/// - `filename` Name of the PNG file.
+pub fn zcr_png_visualize(
+    samples: &[f32],
+    sample_rate: u32,
+    window_ms: u32,
+    directory: &str,
+    filename: &str,
+) {
+    let zcr = zero_crossing_rate(samples, sample_rate, window_ms);
+    let duration = samples.len() as f32 / sample_rate as f32;
+    draw_over_time(
+        zcr,
+        duration,
+        "zero-crossing rate",
+        "zero crossings per second",
+        directory,
+        filename,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::testutil::TEST_OUT_DIR;
+
+    #[test]
+    fn test_zero_crossing_rate() {
+        // a sine wave crosses zero twice per period; silence never
+        let mut samples = (0..44100)
+            .map(|i| i as f32 / 44100.0)
+            .map(|t| (t * 441.0 * 2.0 * std::f32::consts::PI + 0.1).sin())
+            .collect::<Vec<_>>();
+        samples.extend(vec![0.0; 44100]);
+
+        let zcr = zero_crossing_rate(&samples, 44100, 100);
+        assert_eq!(zcr.len(), 20);
+        assert!((zcr[0].1 - 882.0).abs() < 20.0, "{}", zcr[0].1);
+        assert_eq!(zcr[19].1, 0.0);
+    }
+
+    #[test]
+    fn test_zcr_png_visualize() {
+        // tone (low ZCR) followed by a deterministic pseudo noise (high ZCR)
+        let mut samples = (0..44100)
+            .map(|i| i as f32 / 44100.0)
+            .map(|t| (t * 200.0 * 2.0 * std::f32::consts::PI).sin())
+            .collect::<Vec<_>>();
+        let mut state = 1_u32;
+        samples.extend((0..44100).map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (state >> 16) as f32 / u16::MAX as f32 * 2.0 - 1.0
+        }));
+        zcr_png_visualize(
+            &samples,
+            44100,
+            20,
+            TEST_OUT_DIR,
+            "zcr_png_visualize_tone_then_noise.png",
+        );
+    }
+}